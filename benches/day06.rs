@@ -0,0 +1,32 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use adventofcode_2023::day06::Race;
+
+// cargo bench --bench day06: the closed form against trying every hold, on races longer and longer
+fn main() {
+    println!("{:>12} {:>14} {:>18}", "Time", "Closed form", "Every hold");
+    for time in [71_530_u64, 7_153_000, 71_530_000] {
+        let race = Race::from(&format!("Time: {time}\nDistance: {}", time * 13));
+        assert_eq!(race.winning_ways(), race.winning_ways_by_trying_every_hold());
+
+        let closed_form = measure(|| black_box(&race).winning_ways());
+        let every_hold = measure(|| black_box(&race).winning_ways_by_trying_every_hold());
+        println!("{:>12} {:>14} {:>18}", time, format!("{closed_form:.1?}"), format!("{every_hold:.1?}"));
+    }
+}
+
+// fastest of a few rounds, each one repeating the call for at least 100ms
+fn measure(solve: impl Fn() -> u64) -> Duration {
+    (0..5)
+        .map(|_| {
+            let started = Instant::now();
+            let mut calls = 0;
+            while started.elapsed() < Duration::from_millis(100) {
+                black_box(solve());
+                calls += 1;
+            }
+            started.elapsed() / calls
+        })
+        .min()
+        .unwrap()
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13};
+use crate::{day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+
+pub type Solver = fn(&str) -> String;
+
+pub struct Day {
+    pub day: u32,
+    pub parts: Vec<Part>,
+}
+
+pub struct Part {
+    pub part: u32,
+    pub solve: Solver,
+    pub ignored: bool,
+}
+
+impl Day {
+    pub fn new(day: u32, part1: Solver, part2: Option<Solver>) -> Day {
+        let mut parts = vec![Part { part: 1, solve: part1, ignored: false }];
+        if let Some(part2) = part2 {
+            parts.push(Part { part: 2, solve: part2, ignored: false });
+        }
+        Day { day, parts }
+    }
+
+    // same as #[ignore] on the test: too slow to run with the others
+    pub fn ignore(mut self, part: u32) -> Day {
+        self.parts.iter_mut().filter(|p| p.part == part).for_each(|p| p.ignored = true);
+        self
+    }
+}
+
+pub fn days() -> Vec<Day> {
+    vec![
+        Day::new(1, day01::part1, Some(day01::part2)),
+        Day::new(2, day02::part1, Some(day02::part2)),
+        Day::new(3, day03::part1, Some(day03::part2)),
+        Day::new(4, day04::part1, Some(day04::part2)),
+        Day::new(5, day05::part1, Some(day05::part2)),
+        Day::new(6, day06::part1, Some(day06::part2)),
+        Day::new(7, day07::part1, Some(day07::part2)),
+        Day::new(8, day08::part1, Some(day08::part2)),
+        Day::new(9, day09::part1, Some(day09::part2)),
+        Day::new(10, day10::part1, Some(day10::part2)),
+        Day::new(11, day11::part1, Some(day11::part2)),
+        Day::new(12, day12::part1, Some(day12::part2)),
+        Day::new(13, day13::part1, Some(day13::part2)),
+        Day::new(14, day14::part1, Some(day14::part2)),
+        Day::new(15, day15::part1, Some(day15::part2)),
+        Day::new(16, day16::part1, Some(day16::part2)),
+        Day::new(17, day17::part1, Some(day17::part2)),
+        Day::new(18, day18::part1, Some(day18::part2)),
+        Day::new(19, day19::part1, Some(day19::part2)),
+        Day::new(20, day20::part1, Some(day20::part2)),
+        Day::new(21, day21::part1, Some(day21::part2)),
+        Day::new(22, day22::part1, Some(day22::part2)),
+        Day::new(23, day23::part1, Some(day23::part2)).ignore(2),
+        Day::new(24, day24::part1, Some(day24::part2)),
+        Day::new(25, day25::part1, None),
+    ]
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Status {
+    Correct,
+    Wrong,
+    Unknown,
+}
+
+pub struct Answers {
+    answers: HashMap<(u32, u32), String>,
+}
+
+impl Answers {
+    pub fn load() -> Answers {
+        Answers::from(&fs::read_to_string("src/answers.txt").unwrap_or_default())
+    }
+
+    pub fn from(input: &str) -> Answers {
+        let answers = input.lines()
+            .filter_map(|l| {
+                let mut fields = l.split_whitespace();
+                let day = fields.next()?.parse().ok()?;
+                let part = fields.next()?.parse().ok()?;
+                Some(((day, part), fields.next()?.to_string()))
+            })
+            .collect();
+        Answers { answers }
+    }
+
+    pub fn verify(&self, day: u32, part: u32, answer: &str) -> Status {
+        match self.answers.get(&(day, part)) {
+            Some(expected) if expected == answer => Status::Correct,
+            Some(_) => Status::Wrong,
+            None => Status::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_every_day_in_order() {
+        let days: Vec<u32> = days().iter().map(|d| d.day).collect();
+
+        assert_eq!(days, (1..=25).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn ignores_slow_parts() {
+        let ignored: Vec<(u32, u32)> = days().iter()
+            .flat_map(|d| d.parts.iter().filter(|p| p.ignored).map(|p| (d.day, p.part)))
+            .collect();
+
+        assert_eq!(ignored, vec![(23, 2)]);
+    }
+
+    #[test]
+    fn verifies_answers() {
+        let answers = Answers::from("1 1 142\n1 2 281\n");
+
+        assert_eq!(answers.verify(1, 1, "142"), Status::Correct);
+        assert_eq!(answers.verify(1, 2, "280"), Status::Wrong);
+        assert_eq!(answers.verify(2, 1, "8"), Status::Unknown);
+    }
+
+    #[test]
+    fn stores_an_answer_for_each_registered_part() {
+        let answers = Answers::load();
+
+        for day in days() {
+            for part in day.parts {
+                assert_ne!(answers.verify(day.day, part.part, ""), Status::Unknown, "day {} part {}", day.day, part.part);
+            }
+        }
+    }
+}
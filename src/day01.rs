@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::io::BufRead;
+use aho_corasick::AhoCorasick;
+use crate::parse;
+use crate::parse::ParseError;
 
 struct CalibrationDocument {
     calibrations: Vec<Calibration>,
@@ -9,15 +12,52 @@ impl CalibrationDocument {
         CalibrationDocument { calibrations: lines.iter().map(|l| Calibration::new(l)).collect() }
     }
 
-    fn sum_values(&self) -> u32 {
-        self.calibrations.iter().map(|c| c.value()).sum()
+    fn sum_values(&self) -> Option<u64> {
+        self.sum_values_with(&Vocabulary::numeric().scanner())
     }
 
-    fn sum_values_spelled_out(&self) -> u32 {
-        self.calibrations.iter().map(|c| c.value_spelled_out()).sum()
+    fn sum_values_spelled_out(&self) -> Option<u64> {
+        self.sum_values_with(&Vocabulary::english().scanner())
+    }
+
+    // None as soon as a line has no digit
+    fn sum_values_with(&self, scanner: &DigitScanner) -> Option<u64> {
+        self.calibrations.iter().map(|c| c.value_with(scanner).map(u64::from)).sum()
+    }
+
+    // one line in memory at a time, every line without digits is reported before giving up
+    fn sum_values_from(mut reader: impl BufRead, scanner: &DigitScanner) -> Result<u64, Vec<CalibrationError>> {
+        let mut line = String::new();
+        let mut sum: u64 = 0;
+        let mut errors = vec![];
+
+        for number in 1.. {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    errors.push(CalibrationError { line: number, message: e.to_string() });
+                    break;
+                }
+            }
+
+            match Calibration::value_of(line.trim_end_matches(['\n', '\r']), scanner) {
+                Some(value) => sum += value as u64,
+                None => errors.push(CalibrationError { line: number, message: "no digit".to_string() }),
+            }
+        }
+
+        if errors.is_empty() { Ok(sum) } else { Err(errors) }
     }
 }
 
+#[derive(PartialEq, Debug)]
+struct CalibrationError {
+    line: usize,
+    message: String,
+}
+
 struct Calibration {
     line: String,
 }
@@ -27,37 +67,152 @@ impl Calibration {
         Calibration { line: line.to_string() }
     }
 
-    pub fn value(&self) -> u32 {
-        self.calculate_value(&self.line)
+    pub fn value(&self) -> Option<u32> {
+        self.value_with(&Vocabulary::numeric().scanner())
+    }
+
+    pub fn value_spelled_out(&self) -> Option<u32> {
+        self.value_with(&Vocabulary::english().scanner())
+    }
+
+    fn value_with(&self, scanner: &DigitScanner) -> Option<u32> {
+        Self::value_of(&self.line, scanner)
+    }
+
+    fn value_of(line: &str, scanner: &DigitScanner) -> Option<u32> {
+        let (first_digit, last_digit) = scanner.first_and_last(line)?;
+
+        Some(first_digit.value * 10 + last_digit.value)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Digit {
+    value: u32,
+    at: usize,
+}
+
+struct DigitScanner {
+    automaton: AhoCorasick,
+    values: Vec<u32>,
+    case_insensitive: bool,
+}
+
+impl DigitScanner {
+    // case is folded on both sides with Unicode lowercasing, not only for ASCII letters
+    pub fn new(vocabulary: &Vocabulary) -> DigitScanner {
+        let tokens = vocabulary.tokens.iter()
+            .map(|(token, _)| if vocabulary.case_insensitive { Self::fold(token).0 } else { token.clone() });
+        let automaton = AhoCorasick::new(tokens).unwrap();
+        DigitScanner { automaton, values: vocabulary.tokens.iter().map(|(_, value)| *value).collect(), case_insensitive: vocabulary.case_insensitive }
+    }
+
+    // lowercases char by char, keeping for each folded byte the offset of the char it comes from,
+    // a char can change length when lowercased ('İ' takes 2 bytes, "i̇" 3)
+    fn fold(text: &str) -> (String, Vec<usize>) {
+        let mut folded = String::with_capacity(text.len());
+        let mut origins = Vec::with_capacity(text.len());
+        for (at, c) in text.char_indices() {
+            folded.extend(c.to_lowercase());
+            origins.resize(folded.len(), at);
+        }
+        (folded, origins)
+    }
+
+    // overlapping matches so "twone" finds both two and one, whatever the order of the tokens,
+    // positions always index the line given, folded or not
+    pub fn first_and_last(&self, line: &str) -> Option<(Digit, Digit)> {
+        let folded;
+        let (line, origins) = if self.case_insensitive {
+            folded = Self::fold(line);
+            (folded.0.as_str(), Some(&folded.1))
+        } else {
+            (line, None)
+        };
+
+        let mut digits = self.automaton.find_overlapping_iter(line)
+            .map(|m| Digit { value: self.values[m.pattern().as_usize()], at: origins.map_or(m.start(), |o| o[m.start()]) });
+
+        let first_digit = digits.next()?;
+        Some(digits.fold((first_digit, first_digit), |(first, last), digit| {
+            (if digit.at < first.at { digit } else { first }, if digit.at > last.at { digit } else { last })
+        }))
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Vocabulary {
+    tokens: Vec<(String, u32)>,
+    case_insensitive: bool,
+}
+
+impl Vocabulary {
+    pub fn numeric() -> Vocabulary {
+        Self::from(NUMERIC).unwrap()
     }
 
-    pub fn value_spelled_out(&self) -> u32 {
-        let new_line = self.replace_spelled_out();
-        self.calculate_value(&new_line)
+    pub fn english() -> Vocabulary {
+        Self::from(&[NUMERIC, ENGLISH].join("\n")).unwrap()
     }
 
-    fn replace_spelled_out(&self) -> String {
-        let spelled_out = HashMap::from([
-            ("one", "one1one"), ("two", "two2two"), ("three", "three3three"),
-            ("four", "four4four"), ("five", "five5five"), ("six", "six6six"),
-            ("seven", "seven7seven"), ("eight", "eight8eight"), ("nine", "nine9nine")
-        ]);
+    pub fn from(table: &str) -> Result<Vocabulary, ParseError<'_>> {
+        let tokens = table.lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let (token, value) = parse::key_value(l, " ")?;
+                Ok((token.to_string(), parse::number(value)?))
+            })
+            .collect::<Result<_, _>>()?;
 
-        spelled_out
-            .into_iter()
-            .fold(self.line.to_owned(), |line, d| line.replace(d.0, d.1))
+        Ok(Vocabulary { tokens, case_insensitive: false })
     }
 
-    fn calculate_value(&self, string: &str) -> u32 {
-        let mut digits = string.chars().filter_map(|c| c.to_digit(10));
+    pub fn with(mut self, token: &str, value: u32) -> Vocabulary {
+        self.tokens.push((token.to_string(), value));
+        self
+    }
 
-        let first_digit = digits.next().unwrap();
-        let last_digit = digits.last().unwrap_or(first_digit);
+    pub fn case_insensitive(mut self) -> Vocabulary {
+        self.case_insensitive = true;
+        self
+    }
 
-        first_digit * 10 + last_digit
+    pub fn scanner(&self) -> DigitScanner {
+        DigitScanner::new(self)
     }
 }
 
+const NUMERIC: &str = "\
+1 1
+2 2
+3 3
+4 4
+5 5
+6 6
+7 7
+8 8
+9 9";
+
+const ENGLISH: &str = "\
+one 1
+two 2
+three 3
+four 4
+five 5
+six 6
+seven 7
+eight 8
+nine 9";
+
+pub fn part1(input: &str) -> String {
+    CalibrationDocument::sum_values_from(input.as_bytes(), &Vocabulary::numeric().scanner()).unwrap().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    CalibrationDocument::sum_values_from(input.as_bytes(), &Vocabulary::english().scanner()).unwrap().to_string()
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -66,17 +221,17 @@ mod tests {
 
     #[test]
     fn finds_value_from_first_and_last_digits() {
-        assert_eq!(Calibration::new("1abc2").value(), 12);
-        assert_eq!(Calibration::new("pqr3stu8vwx").value(), 38);
-        assert_eq!(Calibration::new("a1b2c3d4e5f").value(), 15);
-        assert_eq!(Calibration::new("treb7uchet").value(), 77);
+        assert_eq!(Calibration::new("1abc2").value(), Some(12));
+        assert_eq!(Calibration::new("pqr3stu8vwx").value(), Some(38));
+        assert_eq!(Calibration::new("a1b2c3d4e5f").value(), Some(15));
+        assert_eq!(Calibration::new("treb7uchet").value(), Some(77));
     }
 
     #[test]
     fn solves_example_part1() {
         let calibration_document = CalibrationDocument::new(vec!["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"]);
 
-        assert_eq!(calibration_document.sum_values(), 142);
+        assert_eq!(calibration_document.sum_values(), Some(142));
     }
 
     #[test]
@@ -85,18 +240,34 @@ mod tests {
         let lines = input.lines().collect();
         let calibration_document = CalibrationDocument::new(lines);
 
-        assert_eq!(calibration_document.sum_values(), 53080);
+        assert_eq!(calibration_document.sum_values(), Some(53080));
     }
 
     #[test]
     fn finds_value_taking_spelled_out_value() {
-        assert_eq!(Calibration::new("two1nine").value_spelled_out(), 29);
-        assert_eq!(Calibration::new("eightwothree").value_spelled_out(), 83);
-        assert_eq!(Calibration::new("abcone2threexyz").value_spelled_out(), 13);
-        assert_eq!(Calibration::new("xtwone3four").value_spelled_out(), 24);
-        assert_eq!(Calibration::new("4nineeightseven2").value_spelled_out(), 42);
-        assert_eq!(Calibration::new("zoneight234").value_spelled_out(), 14);
-        assert_eq!(Calibration::new("7pqrstsixteen").value_spelled_out(), 76);
+        assert_eq!(Calibration::new("two1nine").value_spelled_out(), Some(29));
+        assert_eq!(Calibration::new("eightwothree").value_spelled_out(), Some(83));
+        assert_eq!(Calibration::new("abcone2threexyz").value_spelled_out(), Some(13));
+        assert_eq!(Calibration::new("xtwone3four").value_spelled_out(), Some(24));
+        assert_eq!(Calibration::new("4nineeightseven2").value_spelled_out(), Some(42));
+        assert_eq!(Calibration::new("zoneight234").value_spelled_out(), Some(14));
+        assert_eq!(Calibration::new("7pqrstsixteen").value_spelled_out(), Some(76));
+    }
+
+    #[test]
+    fn finds_first_and_last_digits_positions() {
+        let scanner = Vocabulary::english().scanner();
+
+        assert_eq!(scanner.first_and_last("xtwone3four"), Some((Digit { value: 2, at: 1 }, Digit { value: 4, at: 7 })));
+        assert_eq!(scanner.first_and_last("treb7uchet"), Some((Digit { value: 7, at: 4 }, Digit { value: 7, at: 4 })));
+        assert_eq!(scanner.first_and_last("pqrstu"), None);
+    }
+
+    #[test]
+    fn finds_overlapping_spelled_out_digits() {
+        assert_eq!(Calibration::new("twone").value_spelled_out(), Some(21));
+        assert_eq!(Calibration::new("eighthree").value_spelled_out(), Some(83));
+        assert_eq!(Calibration::new("oneight").value_spelled_out(), Some(18));
     }
 
     #[test]
@@ -110,7 +281,7 @@ mod tests {
             "zoneight234",
             "7pqrstsixteen"]);
 
-        assert_eq!(calibration_document.sum_values_spelled_out(), 281);
+        assert_eq!(calibration_document.sum_values_spelled_out(), Some(281));
     }
 
     #[test]
@@ -119,6 +290,73 @@ mod tests {
         let lines = input.lines().collect();
         let calibration_document = CalibrationDocument::new(lines);
 
-        assert_eq!(calibration_document.sum_values_spelled_out(), 53268);
+        assert_eq!(calibration_document.sum_values_spelled_out(), Some(53268));
+    }
+
+    #[test]
+    fn parses_vocabulary_table() {
+        let vocabulary = Vocabulary::from("un 1\n deux 2\n\n").unwrap();
+
+        assert_eq!(vocabulary.tokens, vec![("un".to_string(), 1), ("deux".to_string(), 2)]);
+        assert!(Vocabulary::from("trois").is_err());
+        assert!(Vocabulary::from("trois three").is_err());
+    }
+
+    #[test]
+    fn finds_value_with_other_vocabularies() {
+        let french = Vocabulary::from("un 1\ndeux 2\ntrois 3\nquatre 4\ncinq 5\nsix 6\nsept 7\nhuit 8\nneuf 9").unwrap();
+        let with_zero = Vocabulary::english().with("zero", 0).with("0", 0);
+        let case_insensitive = Vocabulary::english().case_insensitive();
+
+        assert_eq!(Calibration::new("xdeux1neufz").value_with(&french.scanner()), Some(29));
+        assert_eq!(Calibration::new("zero5zeroone").value_with(&with_zero.scanner()), Some(1));
+        assert_eq!(Calibration::new("a0bc9zero").value_with(&with_zero.scanner()), Some(0));
+        assert_eq!(Calibration::new("ONE2thrEE").value_with(&case_insensitive.scanner()), Some(13));
+        assert_eq!(Calibration::new("trebuchet").value_with(&with_zero.scanner()), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn folds_case_beyond_ascii() {
+        let german = Vocabulary::from("zwei 2\ndrei 3\nfünf 5").unwrap().case_insensitive();
+
+        assert_eq!(Calibration::new("FÜNF").value_with(&german.scanner()), Some(55));
+        assert_eq!(Calibration::new("xZweiFünfDREI").value_with(&german.scanner()), Some(23));
+    }
+
+    #[test]
+    fn finds_positions_in_the_original_line_when_folding_case() {
+        let scanner = Vocabulary::from("zwei 2\nfünf 5").unwrap().case_insensitive().scanner();
+        let line = "İstanbulFÜNFİİzwei";
+
+        let (first, last) = scanner.first_and_last(line).unwrap();
+
+        assert_eq!((first.value, last.value), (5, 2));
+        assert!(line[first.at..].starts_with("FÜNF"));
+        assert!(line[last.at..].starts_with("zwei"));
+    }
+
+    #[test]
+    fn sums_document_with_vocabulary() {
+        let calibration_document = CalibrationDocument::new(vec!["un2", "trois"]);
+        let vocabulary = Vocabulary::numeric().with("un", 1).with("trois", 3);
+
+        assert_eq!(calibration_document.sum_values_with(&vocabulary.scanner()), Some(12 + 33));
+    }
+
+    #[test]
+    fn sums_values_from_reader() {
+        let reader = "two1nine\r\neightwothree\nabcone2threexyz\n".as_bytes();
+
+        assert_eq!(CalibrationDocument::sum_values_from(reader, &Vocabulary::english().scanner()), Ok(29 + 83 + 13));
+    }
+
+    #[test]
+    fn reports_lines_without_digits() {
+        let reader = "1abc2\ntrebuchet\n\ntreb7uchet".as_bytes();
+
+        assert_eq!(CalibrationDocument::sum_values_from(reader, &Vocabulary::numeric().scanner()), Err(vec![
+            CalibrationError { line: 2, message: "no digit".to_string() },
+            CalibrationError { line: 3, message: "no digit".to_string() },
+        ]));
+    }
+}
@@ -1,4 +1,5 @@
-use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -15,19 +16,82 @@ mod tests {
         ]
     }
 
+    fn standard_bag() -> Cubes {
+        cubes(&[("red", 12), ("green", 13), ("blue", 14)])
+    }
+
     #[test]
-    fn parses_max_cubes_in_a_game() {
+    fn parses_each_draw_of_a_game() {
         let game = Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
 
         assert_eq!(game.id, 1);
-        assert_eq!(game.blue, 6);
-        assert_eq!(game.green, 2);
-        assert_eq!(game.red, 4);
+        assert_eq!(game.draws, vec![
+            cubes(&[("blue", 3), ("red", 4)]),
+            cubes(&[("red", 1), ("green", 2), ("blue", 6)]),
+            cubes(&[("green", 2)]),
+        ]);
+    }
+
+    #[test]
+    fn finds_minimum_bag_of_a_game() {
+        let game = Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+
+        assert_eq!(game.minimum_bag(), cubes(&[("blue", 6), ("green", 2), ("red", 4)]));
+        assert_eq!(game.power(), 48);
+    }
+
+    #[test]
+    fn supports_arbitrary_colors() {
+        let game = Game::new("Game 7: 2 yellow, 1 red; 5 yellow");
+
+        assert_eq!(game.minimum_bag(), cubes(&[("yellow", 5), ("red", 1)]));
+        assert!(game.is_possible_with(&cubes(&[("yellow", 5), ("red", 1), ("blue", 3)])));
+        assert!(!game.is_possible_with(&standard_bag()));
+    }
+
+    #[test]
+    fn finds_draws_violating_the_bag() {
+        let game = Game::new("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red");
+
+        assert!(!game.is_possible_with(&standard_bag()));
+        assert_eq!(game.violations(&standard_bag()), vec![Violation { draw: 0, color: "red".to_string(), drawn: 20, limit: 12 }]);
+        assert!(game.violations(&game.minimum_bag()).is_empty());
+    }
+
+    #[test]
+    fn infers_lower_bounds_of_the_bag() {
+        let games: Vec<Game> = example_input().iter().map(|l| Game::new(l)).collect();
+
+        assert_eq!(lower_bounds(&games), cubes(&[("red", 20), ("green", 13), ("blue", 15)]));
+    }
+
+    #[test]
+    fn finds_feasible_bags_for_a_total() {
+        let games: Vec<Game> = example_input().iter().map(|l| Game::new(l)).collect();
+
+        assert_eq!(feasible_bags(&games, 48), vec![cubes(&[("red", 20), ("green", 13), ("blue", 15)])]);
+        assert_eq!(feasible_bags(&games, 49).len(), 3);
+        assert_eq!(feasible_bags(&games, 50).len(), 6);
+        assert!(feasible_bags(&games, 47).is_empty());
+        assert!(feasible_bags(&games, 49).iter().all(|bag| games.iter().all(|g| g.is_possible_with(bag))));
+    }
+
+    #[test]
+    fn ranks_bags_by_likelihood() {
+        let games = vec![Game::new("Game 1: 1 red, 1 blue; 1 red; 1 blue")];
+
+        let bags = likely_bags(&games, 4);
+
+        assert_eq!(bags.len(), 3);
+        assert_eq!(bags[0].0, cubes(&[("red", 2), ("blue", 2)]));
+        assert!((bags[0].1.exp() - 4.0 / 6.0 * 2.0 / 4.0 * 2.0 / 4.0).abs() < 1e-9);
+        assert!((bags[1].1.exp() - 3.0 / 6.0 * 1.0 / 4.0 * 3.0 / 4.0).abs() < 1e-9);
+        assert_eq!(games[0].log_likelihood(&cubes(&[("red", 4)])), f64::NEG_INFINITY);
     }
 
     #[test]
     fn solves_part1_example() {
-        assert_eq!(sum_possible_games(example_input()), 8);
+        assert_eq!(sum_possible_games(example_input(), &standard_bag()), 8);
     }
 
     #[test]
@@ -35,7 +99,7 @@ mod tests {
         let input = daily_input(2);
         let lines = input.lines().collect();
 
-        assert_eq!(sum_possible_games(lines), 2771);
+        assert_eq!(sum_possible_games(lines, &standard_bag()), 2771);
     }
 
     #[test]
@@ -52,12 +116,20 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    sum_possible_games(input.lines().collect(), &cubes(&[("red", 12), ("green", 13), ("blue", 14)])).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    sum_power_of_games(input.lines().collect()).to_string()
+}
+
 
-fn sum_possible_games(lines: Vec<&str>) -> u32 {
+fn sum_possible_games(lines: Vec<&str>, bag: &Cubes) -> u32 {
     lines
         .iter()
         .map(|l| Game::new(l))
-        .filter(|g| g.red <= 12 && g.green <= 13 && g.blue <= 14)
+        .filter(|g| g.is_possible_with(bag))
         .map(|g| g.id)
         .sum()
 }
@@ -66,34 +138,140 @@ fn sum_power_of_games(lines: Vec<&str>) -> u32 {
     lines
         .iter()
         .map(|l| Game::new(l))
-        .map(|g| g.red * g.green * g.blue)
+        .map(|g| g.power())
         .sum()
 }
 
+fn lower_bounds(games: &[Game]) -> Cubes {
+    let mut bounds = Cubes::new();
+    for (color, count) in games.iter().flat_map(|g| g.minimum_bag()) {
+        let bound = bounds.entry(color).or_default();
+        *bound = (*bound).max(count);
+    }
+    bounds
+}
+
+// only the colors seen in a game can be in the bag, each one at least at its lower bound
+fn feasible_bags(games: &[Game], total: u32) -> Vec<Cubes> {
+    let bounds: Vec<(String, u32)> = lower_bounds(games).into_iter().collect();
+    let spare = total.checked_sub(bounds.iter().map(|(_, count)| count).sum());
+
+    let mut bags = vec![];
+    if let Some(spare) = spare.filter(|_| !bounds.is_empty()) {
+        distribute(&bounds, spare, &mut Cubes::new(), &mut bags);
+    }
+    bags
+}
+
+fn distribute(bounds: &[(String, u32)], spare: u32, bag: &mut Cubes, bags: &mut Vec<Cubes>) {
+    let ((color, bound), rest) = bounds.split_first().unwrap();
+    let extras = if rest.is_empty() { spare..=spare } else { 0..=spare };
+
+    for extra in extras {
+        bag.insert(color.clone(), bound + extra);
+        if rest.is_empty() {
+            bags.push(bag.clone());
+        } else {
+            distribute(rest, spare - extra, bag, bags);
+        }
+    }
+}
+
+// most likely first, each draw being taken without replacement and put back before the next one
+fn likely_bags(games: &[Game], total: u32) -> Vec<(Cubes, f64)> {
+    let mut bags: Vec<(Cubes, f64)> = feasible_bags(games, total).into_iter()
+        .map(|bag| {
+            let likelihood = games.iter().map(|g| g.log_likelihood(&bag)).sum();
+            (bag, likelihood)
+        })
+        .collect();
+    bags.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    bags
+}
+
+fn ln_choose(n: u32, k: u32) -> f64 {
+    (0..k).map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln()).sum()
+}
+
+// count of cubes per color, for a single draw as well as for a bag
+type Cubes = BTreeMap<String, u32>;
+
+fn cubes(counts: &[(&str, u32)]) -> Cubes {
+    counts.iter().map(|(color, count)| (color.to_string(), *count)).collect()
+}
+
+#[derive(PartialEq, Debug)]
 struct Game {
     id: u32,
-    blue: u32,
-    green: u32,
-    red: u32
+    draws: Vec<Cubes>,
+}
+
+#[derive(PartialEq, Debug)]
+struct Violation {
+    draw: usize,
+    color: String,
+    drawn: u32,
+    limit: u32,
 }
 
 impl Game {
     fn new(line: &str) -> Game {
-        let regex = Regex::new(r"(Game (?<id>[0-9]+)|(?<cubes>[0-9]+) (?<color>blue|green|red))").unwrap();
-        let groups: Vec<Captures> = regex.captures_iter(line).collect();
+        let (game, draws) = parse::section(line).unwrap();
+        let (_, id) = parse::key_value(game, " ").unwrap();
+
+        let id = parse::number(id).unwrap();
+        let draws = draws.split(';').map(Self::draw).collect();
+
+        Game { id, draws }
+    }
+
+    fn draw(draw: &str) -> Cubes {
+        let mut cubes = Cubes::new();
+        for (count, color) in draw.split(',').map(|c| parse::key_value(c.trim(), " ").unwrap()) {
+            *cubes.entry(color.to_string()).or_default() += parse::number::<u32>(count).unwrap();
+        }
+        cubes
+    }
+
+    fn minimum_bag(&self) -> Cubes {
+        let mut bag = Cubes::new();
+        for (color, count) in self.draws.iter().flatten() {
+            let minimum = bag.entry(color.clone()).or_default();
+            *minimum = (*minimum).max(*count);
+        }
+        bag
+    }
+
+    fn power(&self) -> u32 {
+        self.minimum_bag().values().product()
+    }
+
+    // multivariate hypergeometric probability of every draw, as a natural logarithm
+    fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        if !self.is_possible_with(bag) {
+            return f64::NEG_INFINITY;
+        }
 
-        let id = groups.first().unwrap()["id"].parse().unwrap();
-        let blue = Self::max_cubes_of_color(&groups, "blue");
-        let green = Self::max_cubes_of_color(&groups, "green");
-        let red = Self::max_cubes_of_color(&groups, "red");
+        let total = bag.values().sum();
+        self.draws.iter()
+            .map(|draw| {
+                let drawn = draw.values().sum();
+                draw.iter().map(|(color, count)| ln_choose(bag.get(color).copied().unwrap_or(0), *count)).sum::<f64>() - ln_choose(total, drawn)
+            })
+            .sum()
+    }
 
-        Game { id, blue, green, red }
+    fn is_possible_with(&self, bag: &Cubes) -> bool {
+        self.violations(bag).is_empty()
     }
 
-    fn max_cubes_of_color(groups: &[Captures], color: &str) -> u32 {
-        groups.iter().skip(1)
-            .filter(|g| &g["color"] == color)
-            .map(|g| g["cubes"].parse().unwrap())
-            .max().unwrap()
+    // a color missing from the bag has a limit of 0
+    fn violations(&self, bag: &Cubes) -> Vec<Violation> {
+        self.draws.iter()
+            .enumerate()
+            .flat_map(|(i, draw)| draw.iter().map(move |(color, count)| (i, color, *count)))
+            .map(|(draw, color, drawn)| Violation { draw, color: color.clone(), drawn, limit: bag.get(color).copied().unwrap_or(0) })
+            .filter(|v| v.drawn > v.limit)
+            .collect()
     }
-}
\ No newline at end of file
+}
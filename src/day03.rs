@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use itertools::Itertools;
+use crate::parse;
 
 #[cfg(test)]
 mod part1 {
@@ -24,10 +26,10 @@ mod part1 {
         let schematic = Schematic::new("467..114..\n..35......\n......755.");
 
         let expected = vec![
-            Part { number: 467, at: vec![0, 1, 2] },
-            Part { number: 114, at: vec![5, 6, 7] },
-            Part { number: 35, at: vec![12, 13] },
-            Part { number: 755, at: vec![26, 27, 28] },
+            Part { number: 467, at: vec![(0, 0), (1, 0), (2, 0)] },
+            Part { number: 114, at: vec![(5, 0), (6, 0), (7, 0)] },
+            Part { number: 35, at: vec![(2, 1), (3, 1)] },
+            Part { number: 755, at: vec![(6, 2), (7, 2), (8, 2)] },
         ];
         assert_eq!(schematic.parts, expected);
     }
@@ -37,20 +39,29 @@ mod part1 {
         let schematic = Schematic::new("467..114..\n...*......\n...$.*....");
 
         let expected = vec![
-            Symbol { c: '*', at: 13 },
-            Symbol { c: '$', at: 23 },
-            Symbol { c: '*', at: 25 },
+            Symbol { c: '*', at: (3, 1) },
+            Symbol { c: '$', at: (3, 2) },
+            Symbol { c: '*', at: (5, 2) },
         ];
         assert_eq!(schematic.symbols, expected);
     }
 
     #[test]
-    fn parses_line_length_from_schematic() {
-        let schematic = Schematic::new("467..114..\n...*......\n...$.*....");
+    fn parses_ragged_lines() {
+        let schematic = Schematic::new("467\n...*12\n.\n5");
 
-        assert_eq!(schematic.length, 10);
+        let numbers: Vec<i32> = schematic.parts.iter().map(|p| p.number).collect();
+        assert_eq!(numbers, vec![467, 12, 5]);
+        assert_eq!(schematic.symbols, vec![Symbol { c: '*', at: (3, 1) }]);
+        assert_eq!(schematic.parts_near_symbol(), vec![&schematic.parts[0], &schematic.parts[1]]);
     }
 
+    #[test]
+    fn does_not_wrap_around_line_ends() {
+        let schematic = Schematic::new("..1\n*..\n");
+
+        assert!(schematic.parts_near_symbol().is_empty());
+    }
 
     #[test]
     fn determines_when_a_symbol_is_nearby() {
@@ -59,7 +70,25 @@ mod part1 {
                      ...*.....\n\
                      11..444..\n");
         let parts = schematic.parts_near_symbol();
-        assert_eq!(parts, vec![&Part { number: 467, at: vec![0, 1, 2] }, &Part { number: 444, at: vec![22, 23, 24] }])
+        assert_eq!(parts, vec![&Part { number: 467, at: vec![(0, 0), (1, 0), (2, 0)] }, &Part { number: 444, at: vec![(4, 2), (5, 2), (6, 2)] }])
+    }
+
+    #[test]
+    fn links_parts_and_symbols() {
+        let schematic = Schematic::new(&example_input());
+
+        let around_first_star: Vec<i32> = schematic.parts_touching_symbol(0).iter().map(|p| p.number).collect();
+        assert_eq!(around_first_star, vec![467, 35]);
+        assert_eq!(schematic.symbols_touching_part(0), vec![&Symbol { c: '*', at: (3, 1) }]);
+        assert!(schematic.symbols_touching_part(1).is_empty());
+    }
+
+    #[test]
+    fn finds_orphan_parts() {
+        let schematic = Schematic::new(&example_input());
+
+        let orphans: Vec<i32> = schematic.orphan_parts().iter().map(|p| p.number).collect();
+        assert_eq!(orphans, vec![114, 58]);
     }
 
     #[test]
@@ -89,6 +118,31 @@ mod part2 {
         assert_eq!(schematic.sum_gear_ratios(), 467835);
     }
 
+    #[test]
+    fn lists_gears_with_their_position() {
+        let schematic = Schematic::new(&part1::example_input());
+
+        assert_eq!(schematic.gears(&GearRule::standard()), vec![
+            Gear { symbol: '*', at: (3, 1), parts: vec![467, 35], ratio: 16345 },
+            Gear { symbol: '*', at: (5, 8), parts: vec![755, 598], ratio: 451490 },
+        ]);
+    }
+
+    #[test]
+    fn evaluates_several_gear_rules() {
+        let schematic = Schematic::new(&part1::example_input());
+        let rules = [
+            GearRule::new('*', 1, Aggregation::Sum),
+            GearRule::new('*', 2, Aggregation::Max),
+            GearRule::new('#', 1, Aggregation::Product),
+            GearRule::new('$', 2, Aggregation::Product),
+        ];
+
+        let gears: Vec<Vec<i32>> = schematic.evaluate(&rules).iter().map(|(_, g)| g.iter().map(|g| g.ratio).collect()).collect();
+
+        assert_eq!(gears, vec![vec![617], vec![467, 755], vec![633], vec![]]);
+    }
+
     #[test]
     fn solves_part2() {
         let schematic = Schematic::new(&daily_input(3));
@@ -97,77 +151,102 @@ mod part2 {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Schematic::new(input).sum_part_numbers().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Schematic::new(input).sum_gear_ratios().to_string()
+}
+
 #[derive(PartialEq, Debug)]
 struct Schematic {
     parts: Vec<Part>,
     symbols: Vec<Symbol>,
-    length: i32,
+    adjacency: Vec<(usize, usize)>,
 }
 
 #[derive(PartialEq, Debug)]
 struct Part {
     number: i32,
-    at: Vec<i32>,
+    at: Vec<(i32, i32)>,
 }
 
 #[derive(PartialEq, Debug)]
 struct Symbol {
     c: char,
-    at: i32,
+    at: (i32, i32),
 }
 
+const NEARBY: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
 
 impl Schematic {
     fn new(input: &str) -> Schematic {
         let mut parts = vec![];
         let mut symbols = vec![];
-        let length = input.find('\n').unwrap() as i32;
 
         let mut part_number = String::new();
-        for (i, char) in input.chars().filter(|c| *c != '\n').enumerate() {
+        let mut part_at = vec![];
+        for (x, y, char) in parse::grid(input) {
+            let at = (x as i32, y as i32);
+            if part_at.last().is_some_and(|(_, part_y)| *part_y != at.1) {
+                Self::build_part(&mut parts, &mut part_number, &mut part_at);
+            }
             match char {
-                c if c.is_ascii_digit() => part_number.push(c),
-                '.' => {
-                    if part_number.is_empty() { continue; }
-                    Self::build_part(&mut parts, &mut part_number, i);
+                c if c.is_ascii_digit() => {
+                    part_number.push(c);
+                    part_at.push(at);
                 }
+                '.' => Self::build_part(&mut parts, &mut part_number, &mut part_at),
                 _ => {
-                    let symbol = Symbol { c: char, at: i as i32 };
-                    symbols.push(symbol);
-
-                    if part_number.is_empty() { continue; }
-                    Self::build_part(&mut parts, &mut part_number, i);
+                    symbols.push(Symbol { c: char, at });
+                    Self::build_part(&mut parts, &mut part_number, &mut part_at);
                 }
             }
         }
-        Schematic { parts, symbols, length }
+        Self::build_part(&mut parts, &mut part_number, &mut part_at);
+
+        let adjacency = Self::adjacency(&parts, &symbols);
+        Schematic { parts, symbols, adjacency }
     }
 
-    fn build_part(parts: &mut Vec<Part>, part_number: &mut String, i: usize) {
-        let part = Part {
-            number: part_number.parse().unwrap(),
-            at: (i - part_number.len()..i).map(|n| n as i32).collect(),
-        };
-        parts.push(part);
+    fn build_part(parts: &mut Vec<Part>, part_number: &mut String, part_at: &mut Vec<(i32, i32)>) {
+        if part_number.is_empty() { return; }
+
+        parts.push(Part { number: part_number.parse().unwrap(), at: std::mem::take(part_at) });
         part_number.clear();
     }
 
-    fn parts_near_symbol(&self) -> Vec<&Part> {
-        let nearby = self.nearby_locations();
-        let symbols_location: Vec<i32> = self.symbols.iter().map(|s| s.at).collect();
+    // (part, symbol) pairs, by index
+    fn adjacency(parts: &[Part], symbols: &[Symbol]) -> Vec<(usize, usize)> {
+        let symbols_location: HashMap<(i32, i32), usize> = symbols.iter().enumerate().map(|(i, s)| (s.at, i)).collect();
+
+        parts.iter().enumerate()
+            .flat_map(|(part, p)| {
+                p.at.iter()
+                    .flat_map(|(x, y)| NEARBY.iter().map(move |(dx, dy)| (x + dx, y + dy)))
+                    .filter_map(|n| symbols_location.get(&n).copied())
+                    .sorted()
+                    .dedup()
+                    .map(move |symbol| (part, symbol))
+            })
+            .collect()
+    }
+
+    fn parts_touching_symbol(&self, symbol: usize) -> Vec<&Part> {
+        self.adjacency.iter().filter(|(_, s)| *s == symbol).map(|(p, _)| &self.parts[*p]).collect()
+    }
+
+    fn symbols_touching_part(&self, part: usize) -> Vec<&Symbol> {
+        self.adjacency.iter().filter(|(p, _)| *p == part).map(|(_, s)| &self.symbols[*s]).collect()
+    }
 
-        self.parts.iter().filter(|p| {
-            let part_near_by: Vec<i32> = p.at.iter().flat_map(|at| nearby.iter().map(|n| *n + *at)).collect();
-            return part_near_by.iter().any(|n| symbols_location.contains(n));
-        }).collect()
+    fn parts_near_symbol(&self) -> Vec<&Part> {
+        self.adjacency.iter().map(|(p, _)| *p).dedup().map(|p| &self.parts[p]).collect()
     }
 
-    fn nearby_locations(&self) -> [i32; 8] {
-        [
-            -self.length - 1, -self.length, -self.length + 1,
-            -1, 1,
-            self.length - 1, self.length, self.length + 1
-        ]
+    fn orphan_parts(&self) -> Vec<&Part> {
+        (0..self.parts.len()).filter(|p| self.symbols_touching_part(*p).is_empty()).map(|p| &self.parts[p]).collect()
     }
 
     fn sum_part_numbers(&self) -> i32 {
@@ -175,22 +254,65 @@ impl Schematic {
     }
 
     fn sum_gear_ratios(&self) -> i32 {
-        let nearby = self.nearby_locations();
-        let star_locations: Vec<i32> = self.symbols.iter().filter(|s| s.c == '*').map(|s| s.at).collect();
-
-        let mut sum: i32 = 0;
-        for star_location in star_locations {
-            let parts_around: Vec<&Part> = self.parts.iter().filter(|p| {
-                let part_near_by: Vec<i32> = p.at.iter().flat_map(|at| nearby.iter().map(|n| *n + *at)).collect();
-                return part_near_by.iter().contains(&star_location);
-            }).collect();
-
-            if parts_around.len() == 2 {
-                let product: i32 = parts_around.iter().map(|p| p.number).product();
-                sum += product;
-            }
-        }
+        self.gears(&GearRule::standard()).iter().map(|g| g.ratio).sum()
+    }
+
+    fn gears(&self, rule: &GearRule) -> Vec<Gear> {
+        self.symbols.iter().enumerate()
+            .filter(|(_, s)| s.c == rule.symbol)
+            .map(|(i, s)| (s, self.parts_touching_symbol(i)))
+            .filter(|(_, parts_around)| parts_around.len() == rule.parts)
+            .map(|(s, parts_around)| {
+                let parts: Vec<i32> = parts_around.iter().map(|p| p.number).collect();
+                Gear { symbol: s.c, at: s.at, ratio: rule.aggregation.apply(&parts), parts }
+            })
+            .collect()
+    }
+
+    fn evaluate<'a>(&self, rules: &'a [GearRule]) -> Vec<(&'a GearRule, Vec<Gear>)> {
+        rules.iter().map(|rule| (rule, self.gears(rule))).collect()
+    }
+}
 
-        sum
+#[derive(PartialEq, Debug)]
+struct GearRule {
+    symbol: char,
+    parts: usize,
+    aggregation: Aggregation,
+}
+
+#[derive(PartialEq, Debug)]
+enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+#[derive(PartialEq, Debug)]
+struct Gear {
+    symbol: char,
+    at: (i32, i32),
+    parts: Vec<i32>,
+    ratio: i32,
+}
+
+impl GearRule {
+    fn new(symbol: char, parts: usize, aggregation: Aggregation) -> GearRule {
+        GearRule { symbol, parts, aggregation }
+    }
+
+    // a * adjacent to exactly two parts, worth their product
+    fn standard() -> GearRule {
+        Self::new('*', 2, Aggregation::Product)
+    }
+}
+
+impl Aggregation {
+    fn apply(&self, numbers: &[i32]) -> i32 {
+        match self {
+            Aggregation::Product => numbers.iter().product(),
+            Aggregation::Sum => numbers.iter().sum(),
+            Aggregation::Max => numbers.iter().copied().max().unwrap_or(0),
+        }
     }
-}
\ No newline at end of file
+}
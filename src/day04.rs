@@ -1,5 +1,5 @@
-use std::collections::{HashMap, HashSet};
-use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -49,6 +49,32 @@ mod tests {
         assert_eq!(Scratchcards::from(example_input()).number_of_cards(), 30)
     }
 
+    #[test]
+    fn counts_copies_of_each_card() {
+        let cascade = Scratchcards::from(example_input()).cascade(CopyRule::Capped);
+
+        assert_eq!(cascade.copies.values().copied().collect::<Vec<u32>>(), vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(cascade.total(), 30);
+    }
+
+    #[test]
+    fn traces_which_card_produced_copies() {
+        let cascade = Scratchcards::from(example_input()).cascade(CopyRule::Capped);
+
+        assert_eq!(cascade.produced_by(2), vec![&Production { from: 2, to: 3, copies: 2 }, &Production { from: 2, to: 4, copies: 2 }]);
+        let copies_of_5: Vec<(u32, u32)> = cascade.produced_for(5).iter().map(|p| (p.from, p.copies)).collect();
+        assert_eq!(copies_of_5, vec![(1, 1), (3, 4), (4, 8)]);
+        assert!(cascade.produced_by(6).is_empty());
+    }
+
+    #[test]
+    fn caps_or_wraps_copies_past_the_end() {
+        let scratchcards = Scratchcards::from(vec!["Card 1: 1 | 2", "Card 2: 1 2 3 | 1 2 3"]);
+
+        assert_eq!(scratchcards.cascade(CopyRule::Capped).copies, BTreeMap::from([(1, 1), (2, 1)]));
+        assert_eq!(scratchcards.cascade(CopyRule::Wrapping).copies, BTreeMap::from([(1, 3), (2, 2)]));
+    }
+
     #[test]
     fn solves_part2() {
         let input = daily_input(4);
@@ -58,6 +84,14 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Scratchcards::from(input.lines().collect()).points().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Scratchcards::from(input.lines().collect()).number_of_cards().to_string()
+}
+
 struct Scratchcards {
     cards: Vec<Scratchcard>,
 }
@@ -84,37 +118,79 @@ impl Scratchcards {
     }
 
     pub fn number_of_cards(&self) -> u32 {
-        let mut cards_by_id = HashMap::new();
-
-        for card in self.cards.iter() {
-            *cards_by_id.entry(card.id).or_insert(0) += 1;
+        self.cascade(CopyRule::Capped).total()
+    }
 
-            let card_count = cards_by_id.get(&card.id).copied().unwrap();
-            for i in 0..card.count_winning() {
-                *cards_by_id.entry(card.id + i + 1).or_insert(0) += card_count;
+    // with wrapping, copies landing on an already scratched card are kept but not scratched again,
+    // otherwise the cascade would never end
+    pub fn cascade(&self, rule: CopyRule) -> Cascade {
+        let mut copies = vec![1; self.cards.len()];
+        let mut produced = vec![];
+
+        for (i, card) in self.cards.iter().enumerate() {
+            let count = copies[i];
+            for won in i + 1..=i + card.count_winning() as usize {
+                let won = match rule {
+                    CopyRule::Capped if won >= self.cards.len() => break,
+                    CopyRule::Capped => won,
+                    CopyRule::Wrapping => won % self.cards.len(),
+                };
+                copies[won] += count;
+                produced.push(Production { from: card.id, to: self.cards[won].id, copies: count });
             }
         }
 
-        cards_by_id.values().sum()
+        let copies = self.cards.iter().map(|c| c.id).zip(copies).collect();
+        Cascade { copies, produced }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum CopyRule {
+    Capped,
+    Wrapping,
+}
+
+#[derive(PartialEq, Debug)]
+struct Cascade {
+    copies: BTreeMap<u32, u32>,
+    produced: Vec<Production>,
+}
+
+#[derive(PartialEq, Debug)]
+struct Production {
+    from: u32,
+    to: u32,
+    copies: u32,
+}
+
+impl Cascade {
+    pub fn total(&self) -> u32 {
+        self.copies.values().sum()
+    }
+
+    pub fn produced_by(&self, id: u32) -> Vec<&Production> {
+        self.produced.iter().filter(|p| p.from == id).collect()
+    }
+
+    pub fn produced_for(&self, id: u32) -> Vec<&Production> {
+        self.produced.iter().filter(|p| p.to == id).collect()
     }
 }
 
 impl Scratchcard {
     pub fn from(card: &str) -> Scratchcard {
-        let regex = Regex::new(r"Card\s+(?<id>[0-9]+): (?<winning>[0-9 ]+) \| (?<numbers>[0-9 ]+)").unwrap();
-        let (_, [id, winning, numbers]) = regex.captures(card).map(|g| g.extract()).unwrap();
+        let (card, numbers) = parse::section(card).unwrap();
+        let (_, id) = parse::key_value(card, " ").unwrap();
+        let (winning, numbers) = parse::key_value(numbers, "|").unwrap();
 
         Scratchcard {
-            id: id.parse().unwrap(),
-            winning_numbers: Self::extract_numbers(winning),
-            selected_numbers: Self::extract_numbers(numbers),
+            id: parse::number(id).unwrap(),
+            winning_numbers: parse::numbers(winning).unwrap(),
+            selected_numbers: parse::numbers(numbers).unwrap(),
         }
     }
 
-    fn extract_numbers(winning: &str) -> Vec<u32> {
-        winning.split(' ').map(|n| n.trim()).filter(|n| !n.is_empty()).map(|n| n.parse().unwrap()).collect()
-    }
-
     pub fn count_winning(&self) -> u32 {
         let numbers: HashSet<_> = self.selected_numbers.iter().copied().collect();
         self.winning_numbers.iter().filter(|n| numbers.contains(n)).count() as u32
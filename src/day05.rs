@@ -1,4 +1,5 @@
-use std::str::Split;
+use crate::{parse, trace};
+use crate::parse::ParseError;
 
 #[cfg(test)]
 mod tests {
@@ -19,7 +20,11 @@ mod tests {
         let almanac = Almanac::from(input);
 
         assert_eq!(almanac.maps.len(), 7);
-        let expected_map = Map { ranges: vec![Range { min: 98, max: 100, diff: -48 }, Range { min: 50, max: 98, diff: 2 }] };
+        let expected_map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            ranges: vec![Range { min: 98, max: 100, diff: -48 }, Range { min: 50, max: 98, diff: 2 }],
+        };
         assert_eq!(almanac.maps.into_iter().next().unwrap(), expected_map);
     }
 
@@ -47,9 +52,115 @@ mod tests {
         assert_eq!(almanac.lowest_location(), 46);
     }
 
-    //ran for 2 hours on my mbp...
+    fn map(ranges: Vec<Range>) -> Map {
+        Map { source: "seed".to_string(), destination: "soil".to_string(), ranges }
+    }
+
+    #[test]
+    fn maps_seeds_past_the_end_of_a_range_unchanged() {
+        let map = map(vec![Range { min: 98, max: 100, diff: -48 }]);
+
+        assert_eq!(map.apply(99), 51);
+        assert_eq!(map.apply(100), 100);
+    }
+
+    #[test]
+    fn maps_intervals_split_at_range_boundaries() {
+        let map = map(vec![Range { min: 98, max: 100, diff: -48 }, Range { min: 50, max: 98, diff: 2 }]);
+
+        let mut intervals = map.apply_intervals(vec![Interval { start: 40, end: 99 }]);
+        intervals.sort_by_key(|i| i.start);
+
+        assert_eq!(intervals, vec![Interval { start: 40, end: 50 }, Interval { start: 50, end: 51 }, Interval { start: 52, end: 100 }]);
+    }
+
+    fn location(map: &Map, seed: i64) -> i64 {
+        map.apply_intervals(vec![Interval { start: seed, end: seed + 1 }])[0].start
+    }
+
+    #[test]
+    fn composes_maps_into_a_single_one() {
+        let almanac = Almanac::from(daily_example(5));
+
+        let composed = almanac.composed();
+
+        assert_eq!([79, 14, 55, 13].map(|s| location(&composed, s)), [82, 43, 86, 35]);
+        assert_eq!(composed.ranges.first().unwrap().min, 0);
+        assert_eq!(composed.ranges.last().unwrap().max, END);
+    }
+
+    #[test]
+    fn inverts_composed_map() {
+        let composed = Almanac::from(daily_example(5)).composed();
+
+        assert!(composed.is_bijective());
+        assert_eq!([82, 43, 86, 35].map(|l| location(&composed.inverse(), l)), [79, 14, 55, 13]);
+    }
+
+    #[test]
+    fn finds_seeds_landing_in_locations() {
+        let almanac = Almanac::from_multiple_seeds(daily_example(5));
+
+        assert_eq!(almanac.seeds_landing_in(Interval { start: 46, end: 47 }), vec![Interval { start: 82, end: 83 }]);
+        assert_eq!(almanac.seeds_landing_in(Interval { start: 0, end: 1 }), vec![]);
+    }
+
+    #[test]
+    fn detects_overlapping_and_non_bijective_maps() {
+        let overlapping = map(vec![Range { min: 0, max: 10, diff: 5 }, Range { min: 8, max: 12, diff: 0 }]);
+        let collapsing = map(vec![Range { min: 0, max: 10, diff: 10 }]);
+
+        assert_eq!(overlapping.overlaps(), vec![(0, 1)]);
+        assert!(!overlapping.is_bijective());
+        assert!(collapsing.overlaps().is_empty());
+        assert!(!collapsing.is_bijective());
+        assert_eq!(collapsing.preimage(Interval { start: 12, end: 13 }), vec![Interval { start: 2, end: 3 }, Interval { start: 12, end: 13 }]);
+    }
+
+    #[test]
+    fn validates_example_almanac() {
+        assert!(Almanac::validate(&daily_example(5)).is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_almanac_lines() {
+        let input = "\
+seeds: 79 14 x
+
+seed-to-soil map:
+50 98 2
+52 50 48
+60 90 8
+
+soil-to-fertilizer map:
+0 15 37
+39 0 10 4
+39 60 0
+
+water-to-light map:
+88 18 7";
+
+        let errors: Vec<String> = Almanac::validate(input).iter().map(|e| e.report(input)).collect();
+
+        assert_eq!(errors, vec![
+            "line 1, column 14: expected a number\nseeds: 79 14 x\n             ^",
+            "line 6, column 1: overlaps the range on line 5\n60 90 8\n^^^^^^^",
+            "line 10, column 1: expected destination, source and length\n39 0 10 4\n^^^^^^^^^",
+            "line 11, column 1: empty range\n39 60 0\n^^^^^^^",
+            "line 13, column 1: expected a map from fertilizer\nwater-to-light map:\n^^^^^^^^^^^^^^^^^^",
+        ]);
+    }
+
+    #[test]
+    fn reports_gaps_between_ranges() {
+        let input = "seeds: 1\n\nseed-to-soil map:\n0 0 10\n20 20 5";
+
+        let errors: Vec<String> = Almanac::validate(input).iter().map(|e| e.message.clone()).collect();
+
+        assert_eq!(errors, vec!["gap 10..20 before this range"]);
+    }
+
     #[test]
-    #[ignore]
     fn solve_part2() {
         let input = daily_input(5);
         let almanac = Almanac::from_multiple_seeds(input);
@@ -58,14 +169,25 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Almanac::from(input.to_string()).lowest_location().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Almanac::from_multiple_seeds(input.to_string()).lowest_location().to_string()
+}
+
 #[derive(PartialEq, Debug)]
 struct Almanac {
     seeds: Vec<u64>,
+    intervals: Vec<Interval>,
     maps: Vec<Map>,
 }
 
 #[derive(PartialEq, Debug)]
 struct Map {
+    source: String,
+    destination: String,
     ranges: Vec<Range>,
 }
 
@@ -76,55 +198,255 @@ struct Range {
     diff: i64,
 }
 
+// values never get anywhere near, so shifting the last range can't overflow
+const END: i64 = i64::MAX / 2;
+
+// half open: start is included, end is not
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Interval {
+    start: i64,
+    end: i64,
+}
+
 impl Almanac {
     pub fn from(input: String) -> Almanac {
-        let mut lines = input.split("\n\n");
+        let blocks = parse::blocks(&input);
+        let seeds = Self::parse_seeds(blocks[0]);
         Almanac {
-            seeds: Self::parse_seeds(&mut lines),
-            maps: lines.map(Map::from).collect(),
+            intervals: seeds.iter().map(|&s| Interval { start: s as i64, end: s as i64 + 1 }).collect(),
+            seeds,
+            maps: blocks[1..].iter().map(|b| Map::from(b)).collect(),
         }
     }
 
     pub fn from_multiple_seeds(input: String) -> Almanac {
-        let mut lines = input.split("\n\n");
-        let simple_seeds = Self::parse_seeds(&mut lines);
-        let mut simple_seeds_iter = simple_seeds.iter();
-        let mut seeds = vec![];
-        while let Some(start) = simple_seeds_iter.next() {
-            let end = start + simple_seeds_iter.next().unwrap();
-            let seeds_to_add: Vec<u64> = (*start..end).collect();
-            seeds.extend_from_slice(&seeds_to_add);
-        }
+        let blocks = parse::blocks(&input);
+        let seeds = Self::parse_seeds(blocks[0]);
         Almanac {
+            intervals: seeds.chunks(2).map(|s| Interval { start: s[0] as i64, end: (s[0] + s[1]) as i64 }).collect(),
             seeds,
-            maps: lines.map(Map::from).collect(),
+            maps: blocks[1..].iter().map(|b| Map::from(b)).collect(),
         }
     }
 
-    fn parse_seeds(lines: &mut Split<&str>) -> Vec<u64> {
-        lines.next().unwrap().split(':').next_back().unwrap().split_whitespace().map(|s| s.parse::<u64>().unwrap()).collect()
+    fn parse_seeds(block: &str) -> Vec<u64> {
+        parse::numbers(parse::labeled(block, "seeds").unwrap()).unwrap()
     }
 
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(intervals = self.intervals.len())))]
     pub fn lowest_location(&self) -> u64 {
-        self.seeds.iter().map(|&s| self.maps.iter().fold(s, |s, m| m.apply(s) as u64)).min().unwrap()
+        let locations = self.maps.iter().fold(self.intervals.clone(), |intervals, m| {
+            trace::count!("intervals_mapped", intervals.len());
+            m.apply_intervals(intervals)
+        });
+
+        locations.iter().map(|i| i.start).min().unwrap() as u64
+    }
+
+    pub fn composed(&self) -> Map {
+        let seeds = Map { source: "seed".to_string(), destination: "seed".to_string(), ranges: vec![] };
+        self.maps.iter().fold(seeds.total(), |composed, m| composed.compose(m))
+    }
+
+    pub fn seeds_landing_in(&self, locations: Interval) -> Vec<Interval> {
+        let mut seeds: Vec<Interval> = self.composed().preimage(locations).iter()
+            .flat_map(|p| self.intervals.iter().map(|s| Interval { start: p.start.max(s.start), end: p.end.min(s.end) }))
+            .filter(|i| i.start < i.end)
+            .collect();
+        seeds.sort_by_key(|i| i.start);
+        seeds
+    }
+
+    // every problem found, pointing at the offending text, instead of stopping at the first one
+    pub fn validate(input: &str) -> Vec<ParseError<'_>> {
+        let blocks = parse::blocks(input);
+        let mut errors = vec![];
+
+        match blocks.first().map(|b| parse::labeled(b, "seeds")) {
+            Some(Ok(seeds)) => errors.extend(parse::numbers::<u64>(seeds).err()),
+            Some(Err(error)) => errors.push(error),
+            None => errors.push(ParseError::new("expected seeds", input)),
+        }
+
+        let mut previous = "seed";
+        for block in blocks.iter().skip(1) {
+            let (header, ranges) = match parse::section(block) {
+                Ok(section) => section,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+            match Map::categories(header) {
+                Ok((source, destination)) => {
+                    if source != previous {
+                        errors.push(ParseError::new(&format!("expected a map from {previous}"), header));
+                    }
+                    previous = destination;
+                }
+                Err(error) => errors.push(error),
+            }
+            errors.extend(Map::validate(input, ranges));
+        }
+
+        errors
     }
 }
 
 impl Map {
-    pub fn from(line: &str) -> Map {
-        let mut parts = line.split('\n');
-        parts.next();
-        let ranges = parts.map(|p| {
-            let numbers: Vec<i64> = p.split(' ').map(|n| n.parse().unwrap()).collect();
-            Range { min: numbers[1], max: numbers[1] + numbers[2], diff: numbers[0] - numbers[1] }
-        }).collect();
+    pub fn from(block: &str) -> Map {
+        let (header, ranges) = parse::section(block).unwrap();
+        let (source, destination) = Self::categories(header).unwrap();
+        let ranges = ranges.lines().map(|p| Self::range(p).unwrap()).collect();
+
+        Map { source: source.to_string(), destination: destination.to_string(), ranges }
+    }
+
+    fn categories(header: &str) -> Result<(&str, &str), ParseError<'_>> {
+        let name = header.strip_suffix(" map").ok_or(ParseError::new("expected '... map'", header))?;
+        parse::key_value(name, "-to-")
+    }
+
+    fn range(line: &str) -> Result<Range, ParseError<'_>> {
+        let numbers: Vec<i64> = parse::numbers(line)?;
+        let [destination, source, length] = numbers[..] else {
+            return Err(ParseError::new("expected destination, source and length", line));
+        };
+        Ok(Range { min: source, max: source + length, diff: destination - source })
+    }
+
+    fn validate<'a>(input: &'a str, ranges: &'a str) -> Vec<ParseError<'a>> {
+        let mut errors = vec![];
+        let mut valid: Vec<(&str, Range)> = vec![];
+
+        for line in ranges.lines() {
+            match Self::range(line) {
+                Ok(range) if range.min >= range.max => errors.push(ParseError::new("empty range", line)),
+                Ok(range) => valid.push((line, range)),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        for (i, (line, range)) in valid.iter().enumerate() {
+            if let Some((other, _)) = valid[..i].iter().find(|(_, r)| r.min < range.max && range.min < r.max) {
+                errors.push(ParseError::new(&format!("overlaps the range on line {}", parse::line_number(input, other)), line));
+            }
+        }
+
+        valid.sort_by_key(|(_, r)| r.min);
+        for pair in valid.windows(2) {
+            let ((_, previous), (line, range)) = (&pair[0], &pair[1]);
+            if previous.max < range.min {
+                errors.push(ParseError::new(&format!("gap {}..{} before this range", previous.max, range.min), line));
+            }
+        }
 
-        Map { ranges }
+        errors.sort_by_key(|e| e.span(input).start);
+        errors
     }
 
     pub fn apply(&self, seed: u64) -> i64 {
         self.ranges.iter()
-            .find(|r| r.min <= seed as i64 && r.max >= seed as i64)
+            .find(|r| r.min <= seed as i64 && r.max > seed as i64)
             .map(|r| r.diff + seed as i64).unwrap_or(seed as i64)
     }
+
+    // each range moves the part of the intervals it covers, what no range covers is left as is
+    pub fn apply_intervals(&self, intervals: Vec<Interval>) -> Vec<Interval> {
+        let mut unmapped = intervals;
+        let mut mapped = vec![];
+
+        for range in self.ranges.iter() {
+            let mut remaining = vec![];
+            for interval in unmapped {
+                let start = interval.start.max(range.min);
+                let end = interval.end.min(range.max);
+                if start >= end {
+                    remaining.push(interval);
+                    continue;
+                }
+
+                mapped.push(Interval { start: start + range.diff, end: end + range.diff });
+                if interval.start < start {
+                    remaining.push(Interval { start: interval.start, end: start });
+                }
+                if end < interval.end {
+                    remaining.push(Interval { start: end, end: interval.end });
+                }
+            }
+            unmapped = remaining;
+        }
+
+        mapped.extend(unmapped);
+        mapped
+    }
+
+    // the same function with a range for every value from 0 to END, sorted and without overlaps
+    pub fn total(&self) -> Map {
+        let mut boundaries: Vec<i64> = self.ranges.iter().flat_map(|r| [r.min, r.max]).chain([0, END]).collect();
+        boundaries.sort();
+        boundaries.dedup();
+
+        let mut ranges: Vec<Range> = vec![];
+        for segment in boundaries.windows(2) {
+            let diff = self.ranges.iter().find(|r| r.min <= segment[0] && segment[1] <= r.max).map(|r| r.diff).unwrap_or(0);
+            match ranges.last_mut() {
+                Some(last) if last.diff == diff => last.max = segment[1],
+                _ => ranges.push(Range { min: segment[0], max: segment[1], diff }),
+            }
+        }
+
+        Map { source: self.source.clone(), destination: self.destination.clone(), ranges }
+    }
+
+    pub fn compose(&self, next: &Map) -> Map {
+        let next = next.total();
+        let ranges = self.total().ranges.iter()
+            .flat_map(|r| next.ranges.iter().filter_map(move |n| {
+                let min = r.min.max(n.min - r.diff);
+                let max = r.max.min(n.max - r.diff);
+                (min < max).then_some(Range { min, max, diff: r.diff + n.diff })
+            }))
+            .collect();
+
+        Map { source: self.source.clone(), destination: next.destination.clone(), ranges }
+    }
+
+    // only a function when the map is bijective, see preimage otherwise
+    pub fn inverse(&self) -> Map {
+        Map {
+            source: self.destination.clone(),
+            destination: self.source.clone(),
+            ranges: self.ranges.iter().map(|r| Range { min: r.min + r.diff, max: r.max + r.diff, diff: -r.diff }).collect(),
+        }
+    }
+
+    pub fn preimage(&self, interval: Interval) -> Vec<Interval> {
+        let mut preimage: Vec<Interval> = self.total().ranges.iter()
+            .map(|r| Interval { start: interval.start.max(r.min + r.diff) - r.diff, end: interval.end.min(r.max + r.diff) - r.diff })
+            .filter(|i| i.start < i.end)
+            .collect();
+        preimage.sort_by_key(|i| i.start);
+        preimage
+    }
+
+    pub fn overlaps(&self) -> Vec<(usize, usize)> {
+        (0..self.ranges.len())
+            .flat_map(|a| (a + 1..self.ranges.len()).map(move |b| (a, b)))
+            .filter(|(a, b)| self.ranges[*a].min < self.ranges[*b].max && self.ranges[*b].min < self.ranges[*a].max)
+            .collect()
+    }
+
+    // every value from 0 to END is reached exactly once
+    pub fn is_bijective(&self) -> bool {
+        if !self.overlaps().is_empty() {
+            return false;
+        }
+
+        let mut images: Vec<(i64, i64)> = self.total().ranges.iter().map(|r| (r.min + r.diff, r.max + r.diff)).collect();
+        images.sort();
+        images.first().is_some_and(|i| i.0 == 0)
+            && images.last().is_some_and(|i| i.1 == END)
+            && images.windows(2).all(|w| w[0].1 == w[1].0)
+    }
 }
\ No newline at end of file
@@ -1,3 +1,6 @@
+use std::ops::RangeInclusive;
+use crate::parse;
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -17,6 +20,73 @@ mod tests {
         assert_eq!(race.winning_ways(), 4);
     }
 
+    #[test]
+    fn finds_winning_interval() {
+        assert_eq!(Race { time: 7, distance: 9 }.winning_interval(), Some(2..=5));
+        assert_eq!(Race { time: 30, distance: 200 }.winning_interval(), Some(11..=19));
+        assert_eq!(Race { time: 4, distance: 4 }.winning_interval(), None);
+        assert_eq!(Race { time: 4, distance: 3 }.winning_interval(), Some(2..=2));
+        assert_eq!(Race { time: 3, distance: 10 }.winning_interval(), None);
+    }
+
+    #[test]
+    fn agrees_with_trying_every_hold() {
+        for time in 0..60 {
+            for distance in 0..time * time / 4 + 2 {
+                let race = Race { time, distance };
+                assert_eq!(race.winning_ways(), race.winning_ways_by_trying_every_hold(), "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn handles_races_beyond_u64_products() {
+        let race = Race { time: u64::MAX, distance: u64::MAX };
+
+        assert_eq!(race.winning_interval(), Some(2..=u64::MAX - 2));
+    }
+
+    #[test]
+    fn closed_form_agrees_with_trying_every_hold_on_long_races() {
+        let race = Race { time: 71530 * 10, distance: 940200 * 10 };
+
+        assert_eq!(race.winning_ways(), race.winning_ways_by_trying_every_hold());
+    }
+
+    #[test]
+    fn standard_boat_agrees_with_closed_form() {
+        for time in 0..40 {
+            for distance in 0..time * time / 4 + 2 {
+                assert_eq!(Boat::standard().winning_interval(time, distance), Race { time, distance }.winning_interval());
+            }
+        }
+    }
+
+    #[test]
+    fn finds_optimal_hold() {
+        assert_eq!(Boat::standard().optimal_hold(7), 3);
+        assert_eq!(Boat { acceleration: 2, max_speed: Some(6) }.optimal_hold(7), 3);
+        assert_eq!(Boat { acceleration: 1, max_speed: Some(2) }.optimal_hold(30), 2);
+        assert_eq!(Boat { acceleration: 3, max_speed: None }.optimal_hold(30), 15);
+    }
+
+    #[test]
+    fn finds_winning_interval_per_record() {
+        let boat = Boat { acceleration: 2, max_speed: Some(6) };
+
+        assert_eq!(boat.distance(3, 7), 24);
+        assert_eq!(boat.winning_intervals(7, &[11, 20, 24]), vec![Some(1..=5), Some(3..=3), None]);
+    }
+
+    #[test]
+    fn combines_winning_holds_across_races() {
+        let races = Races::from("Time:      7  15   30\nDistance:  9  40  200");
+
+        assert_eq!(races.product_of_winning_ways_with(&Boat::standard()), 288);
+        assert_eq!(races.union_of_winning_holds_with(&Boat::standard()), vec![2..=19]);
+        assert_eq!(races.union_of_winning_holds_with(&Boat { acceleration: 1, max_speed: Some(8) }), vec![2..=9]);
+    }
+
     #[test]
     fn solves_example_part1() {
         let races = Races::from("Time:      7  15   30\nDistance:  9  40  200");
@@ -55,16 +125,30 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Races::from(input).product_of_winning_ways().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Race::from(input).winning_ways().to_string()
+}
+
 struct Races {
     races: Vec<Race>,
 }
 
 #[derive(PartialEq, Debug)]
-struct Race {
+pub struct Race {
     time: u64,
     distance: u64,
 }
 
+#[derive(PartialEq, Debug)]
+struct Boat {
+    acceleration: u64,
+    max_speed: Option<u64>,
+}
+
 impl Races {
     pub fn from(input: &str) -> Races {
         let mut lines = input.split('\n');
@@ -80,15 +164,33 @@ impl Races {
     }
 
     fn parse_line(line: &str) -> Vec<u64> {
-        line.split_whitespace()
-            .skip(1)
-            .map(|n| n.parse().unwrap())
-            .collect()
+        parse::numbers(parse::section(line).unwrap().1).unwrap()
     }
 
-    pub fn product_of_winning_ways(&self) -> u32 {
+    pub fn product_of_winning_ways(&self) -> u64 {
         self.races.iter().map(|r| r.winning_ways()).product()
     }
+
+    pub fn product_of_winning_ways_with(&self, boat: &Boat) -> u64 {
+        self.races.iter()
+            .map(|r| boat.winning_interval(r.time, r.distance).map(|holds| holds.end() - holds.start() + 1).unwrap_or(0))
+            .product()
+    }
+
+    // hold times winning at least one of the races
+    pub fn union_of_winning_holds_with(&self, boat: &Boat) -> Vec<RangeInclusive<u64>> {
+        let mut intervals: Vec<RangeInclusive<u64>> = self.races.iter().filter_map(|r| boat.winning_interval(r.time, r.distance)).collect();
+        intervals.sort_by_key(|holds| *holds.start());
+
+        let mut union: Vec<RangeInclusive<u64>> = vec![];
+        for holds in intervals {
+            match union.last_mut() {
+                Some(last) if *holds.start() <= last.end().saturating_add(1) => *last = *last.start()..=*last.end().max(holds.end()),
+                _ => union.push(holds),
+            }
+        }
+        union
+    }
 }
 
 impl Race {
@@ -101,13 +203,33 @@ impl Race {
     }
 
     fn parse_line(line: &str) -> u64 {
-        line.split(':')
-            .nth(1).unwrap()
-            .replace(' ', "").parse().unwrap()
+        parse::number(&parse::section(line).unwrap().1.replace(' ', "")).unwrap()
     }
 
 
-    pub fn winning_ways(&self) -> u32 {
+    pub fn winning_ways(&self) -> u64 {
+        self.winning_interval().map(|holds| holds.end() - holds.start() + 1).unwrap_or(0)
+    }
+
+    // holding h wins when h * (time - h) > distance, between the roots of h² - time * h + distance
+    pub fn winning_interval(&self) -> Option<RangeInclusive<u64>> {
+        let (time, distance) = (self.time as u128, self.distance as u128);
+        let discriminant = (time * time).checked_sub(4 * distance)?;
+
+        let mut first = (time - discriminant.isqrt()) / 2;
+        while first <= time && first * (time - first) <= distance {
+            first += 1;
+        }
+        while first > 0 && (first - 1) * (time + 1 - first) > distance {
+            first -= 1;
+        }
+
+        // the distance is symmetric around time / 2
+        let last = time.checked_sub(first)?;
+        (first <= last).then_some(first as u64..=last as u64)
+    }
+
+    pub fn winning_ways_by_trying_every_hold(&self) -> u64 {
         let mut count = 0;
 
         for hold in 0..self.time {
@@ -119,4 +241,59 @@ impl Race {
 
         count
     }
-}
\ No newline at end of file
+}
+
+impl Boat {
+    pub fn standard() -> Boat {
+        Boat { acceleration: 1, max_speed: None }
+    }
+
+    pub fn speed(&self, hold: u64) -> u128 {
+        let speed = hold as u128 * self.acceleration as u128;
+        self.max_speed.map(|max| speed.min(max as u128)).unwrap_or(speed)
+    }
+
+    pub fn distance(&self, hold: u64, time: u64) -> u128 {
+        self.speed(hold) * time.saturating_sub(hold) as u128
+    }
+
+    // the distance grows until the speed is capped or half the time is spent holding, then shrinks
+    pub fn optimal_hold(&self, time: u64) -> u64 {
+        let capped_at = match self.max_speed {
+            Some(max) if self.acceleration > 0 => max / self.acceleration,
+            _ => time,
+        };
+
+        [time / 2, time.div_ceil(2), capped_at, capped_at.saturating_add(1)].into_iter()
+            .map(|hold| hold.min(time))
+            .min_by_key(|&hold| (u128::MAX - self.distance(hold, time), hold))
+            .unwrap()
+    }
+
+    pub fn winning_interval(&self, time: u64, record: u64) -> Option<RangeInclusive<u64>> {
+        let optimal = self.optimal_hold(time);
+        if self.distance(optimal, time) <= record as u128 {
+            return None;
+        }
+
+        let first = Self::partition_point(0, optimal, |hold| self.distance(hold, time) <= record as u128);
+        let last = Self::partition_point(optimal, time, |hold| self.distance(hold, time) > record as u128) - 1;
+        Some(first..=last)
+    }
+
+    pub fn winning_intervals(&self, time: u64, records: &[u64]) -> Vec<Option<RangeInclusive<u64>>> {
+        records.iter().map(|&record| self.winning_interval(time, record)).collect()
+    }
+
+    // first value in low..=high for which predicate is false, predicate being true then false
+    fn partition_point(mut low: u64, mut high: u64, predicate: impl Fn(u64) -> bool) -> u64 {
+        if predicate(high) {
+            return high + 1;
+        }
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if predicate(middle) { low = middle + 1 } else { high = middle }
+        }
+        low
+    }
+}
@@ -1,6 +1,8 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use itertools::Itertools;
+use serde_json::json;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -39,9 +41,9 @@ mod tests {
 
     #[test]
     fn calculates_cards_value() {
-        assert_eq!(Hand::new("23456").cards_value(m1()), vec![2, 3, 4, 5, 6]);
-        assert_eq!(Hand::new("789TJ").cards_value(m1()), vec![7, 8, 9, 10, 11]);
-        assert_eq!(Hand::new("QKAAA").cards_value(m1()), vec![12, 13, 14, 14, 14]);
+        assert_eq!(Hand::new("23456").cards_value(&CardRules::standard()), vec![0, 1, 2, 3, 4]);
+        assert_eq!(Hand::new("789TJ").cards_value(&CardRules::standard()), vec![5, 6, 7, 8, 9]);
+        assert_eq!(Hand::new("QKAAA").cards_value(&CardRules::standard()), vec![10, 11, 12, 12, 12]);
     }
 
     #[test]
@@ -62,7 +64,7 @@ mod tests {
 
     #[test]
     fn solves_example_part1() {
-        let mut hands = Hands::from(example_input());
+        let hands = Hands::from(example_input());
 
         assert_eq!(hands.total_winnings(), 6440);
     }
@@ -71,7 +73,7 @@ mod tests {
     fn solves_part1() {
         let input = daily_input(7);
         let lines = input.lines().collect();
-        let mut hands = Hands::from(lines);
+        let hands = Hands::from(lines);
 
         assert_eq!(hands.total_winnings(), 247815719);
     }
@@ -89,28 +91,137 @@ mod tests {
 
     #[test]
     fn calculates_cards_value_with_jokers() {
-        assert_eq!(Hand::new("23456").cards_value(m2()), vec![2, 3, 4, 5, 6]);
-        assert_eq!(Hand::new("789TJ").cards_value(m2()), vec![7, 8, 9, 10, 0]);
-        assert_eq!(Hand::new("QKAAA").cards_value(m2()), vec![12, 13, 14, 14, 14]);
+        assert_eq!(Hand::new("23456").cards_value(&CardRules::jokers()), vec![1, 2, 3, 4, 5]);
+        assert_eq!(Hand::new("789TJ").cards_value(&CardRules::jokers()), vec![6, 7, 8, 9, 0]);
+        assert_eq!(Hand::new("QKAAA").cards_value(&CardRules::jokers()), vec![10, 11, 12, 12, 12]);
     }
 
     #[test]
     fn solves_example_part2() {
-        let mut hands = Hands::from(example_input());
+        let hands = Hands::from(example_input());
 
         assert_eq!(hands.total_winnings_with_jokers(), 5905);
     }
 
+    #[test]
+    fn scores_hands_of_other_sizes() {
+        let rules = CardRules::new("23456789TJQKA", "", 3, CardRules::hand_types());
+
+        assert_eq!(Hand::new("AAA").score_with(&rules), FiveOfAKind);
+        assert_eq!(Hand::new("AA2").score_with(&rules), OnePair);
+        assert_eq!(Hand::new("AAA222").score_with(&CardRules::new("23456789TJQKA", "", 6, CardRules::hand_types())), FullHouse);
+    }
+
+    #[test]
+    fn replaces_every_wildcard() {
+        let rules = CardRules::new("*?23456789TJQKA", "*?", 5, CardRules::hand_types());
+
+        assert_eq!(Hand::new("2*3?4").score_with(&rules), ThreeOfAKind);
+        assert_eq!(Hand::new("*?*?*").score_with(&rules), FiveOfAKind);
+    }
+
+    #[test]
+    fn ranks_hand_types_by_rules() {
+        let two_pairs_first = CardRules::new("23456789TJQKA", "", 5, vec![HighCard, OnePair, ThreeOfAKind, TwoPairs, FullHouse, FourOfAKind, FiveOfAKind]);
+        let hands = Hands::from(vec!["22234 1", "22334 10"]);
+
+        assert_eq!(hands.total_winnings(), 2 + 10);
+        assert_eq!(hands.total_winnings_with(&two_pairs_first), 10 * 2 + 1);
+    }
+
+    #[test]
+    fn substitutes_wildcards_for_the_strongest_type_by_rules() {
+        let two_pairs_first = CardRules::new("J23456789TQKA", "J", 5, vec![HighCard, OnePair, ThreeOfAKind, TwoPairs, FullHouse, FourOfAKind, FiveOfAKind]);
+        let hand = Hand::new("22J34");
+
+        assert_eq!(hand.score_jokers(), ThreeOfAKind);
+        assert_eq!(hand.score_with(&two_pairs_first), TwoPairs);
+        assert_eq!(hand.substitution(&two_pairs_first), "22434");
+    }
+
+    #[test]
+    fn spreads_wildcards_over_different_cards_when_it_scores_higher() {
+        let two_pairs_first = CardRules::new("J23456789TQKA", "J", 5, vec![HighCard, OnePair, ThreeOfAKind, TwoPairs, FullHouse, FourOfAKind, FiveOfAKind]);
+        let hand = Hand::new("2JJ34");
+
+        assert_eq!(hand.score_jokers(), ThreeOfAKind);
+        assert_eq!(hand.score_with(&two_pairs_first), TwoPairs);
+        assert_eq!(hand.substitution(&two_pairs_first), "24334");
+        assert_eq!(Hand::new("JJJJJ").score_with(&two_pairs_first), FiveOfAKind);
+    }
+
+    #[test]
+    fn ranks_hands_with_explanations() {
+        let hands = Hands::from(example_input());
+
+        let ranking = hands.ranking(&CardRules::jokers());
+
+        let ranked: Vec<(&str, HandType, &str, u32, u32)> = ranking.iter().map(|r| (r.hand.cards.as_str(), r.hand_type, r.substitution.as_str(), r.rank, r.contribution)).collect();
+        assert_eq!(ranked, vec![
+            ("32T3K", OnePair, "32T3K", 1, 765),
+            ("KK677", TwoPairs, "KK677", 2, 56),
+            ("T55J5", FourOfAKind, "T5555", 3, 2052),
+            ("QQQJA", FourOfAKind, "QQQQA", 4, 1932),
+            ("KTJJT", FourOfAKind, "KTTTT", 5, 1100),
+        ]);
+        assert_eq!(ranking[0].explanation, "lowest hand; loses to KK677 with OnePair under TwoPairs");
+        assert_eq!(ranking[2].explanation, "beats KK677 with FourOfAKind over TwoPairs; loses to QQQJA on card 1: T under Q");
+        assert_eq!(ranking[3].explanation, "beats T55J5 on card 1: Q over T; loses to KTJJT on card 1: Q under K");
+        assert_eq!(ranking[4].explanation, "beats QQQJA on card 1: K over Q; highest hand");
+    }
+
+    #[test]
+    fn substitutes_hand_of_jokers() {
+        assert_eq!(Hand::new("JJJJJ").substitution(&CardRules::jokers()), "AAAAA");
+        assert_eq!(Hand::new("JJJJJ").substitution(&CardRules::standard()), "JJJJJ");
+    }
+
+    #[test]
+    fn reports_ranking_as_table_and_json() {
+        let hands = Hands::from(example_input());
+        let ranking = hands.ranking(&CardRules::standard());
+
+        let table = table(&ranking);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[5].starts_with(&format!("{:>4}  {:<8} {:<12} {:<8} {:>6} {:>10}", 5, "QQQJA", "ThreeOfAKind", "QQQJA", 483, 2415)));
+        assert!(lines[6].ends_with("6440"));
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&ranking)).unwrap();
+        assert_eq!(json[4]["hand"], "QQQJA");
+        assert_eq!(json[4]["winnings"], 2415);
+        assert_eq!(json[4]["tie_break"], "beats T55J5 on card 1: Q over T; highest hand");
+    }
+
+    #[test]
+    fn reports_ranking_from_input() {
+        let input = example_input().join("\n");
+
+        assert!(ranking_report(&input, false, false).lines().last().unwrap().ends_with("6440"));
+        assert!(ranking_report(&input, true, false).lines().last().unwrap().ends_with("5905"));
+        assert!(ranking_report(&input, true, true).starts_with('['));
+    }
+
     #[test]
     fn solves_part2() {
         let input = daily_input(7);
         let lines = input.lines().collect();
-        let mut hands = Hands::from(lines);
+        let hands = Hands::from(lines);
 
         assert_eq!(hands.total_winnings_with_jokers(), 248747492);
     }
 }
 
+pub fn part1(input: &str) -> String {
+    let hands = Hands::from(input.lines().collect());
+    hands.total_winnings().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let hands = Hands::from(input.lines().collect());
+    hands.total_winnings_with_jokers().to_string()
+}
+
 struct Hands {
     hands: Vec<Hand>,
 }
@@ -121,7 +232,7 @@ struct Hand {
     bid: u32,
 }
 
-#[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy)]
 enum HandType {
     FiveOfAKind = 6,
     FourOfAKind = 5,
@@ -132,86 +243,258 @@ enum HandType {
     HighCard = 0,
 }
 
+// rank order and hand types go from the weakest to the strongest
+#[derive(PartialEq, Debug)]
+struct CardRules {
+    ranks: Vec<char>,
+    wildcards: Vec<char>,
+    hand_size: usize,
+    hand_types: Vec<HandType>,
+}
+
+impl CardRules {
+    pub fn new(ranks: &str, wildcards: &str, hand_size: usize, hand_types: Vec<HandType>) -> CardRules {
+        CardRules { ranks: ranks.chars().collect(), wildcards: wildcards.chars().collect(), hand_size, hand_types }
+    }
+
+    pub fn standard() -> CardRules {
+        Self::new("23456789TJQKA", "", 5, Self::hand_types())
+    }
+
+    pub fn jokers() -> CardRules {
+        Self::new("J23456789TQKA", "J", 5, Self::hand_types())
+    }
+
+    fn hand_types() -> Vec<HandType> {
+        vec![
+            HandType::HighCard, HandType::OnePair, HandType::TwoPairs, HandType::ThreeOfAKind,
+            HandType::FullHouse, HandType::FourOfAKind, HandType::FiveOfAKind,
+        ]
+    }
+
+    fn rank(&self, card: char) -> u32 {
+        self.ranks.iter().position(|&c| c == card).unwrap_or_else(|| panic!("unknown card {card}")) as u32
+    }
+
+    fn strength(&self, hand_type: HandType) -> usize {
+        self.hand_types.iter().position(|&t| t == hand_type).unwrap_or_else(|| panic!("unranked hand type {hand_type:?}"))
+    }
+}
+
 impl Hands {
     pub fn from(lines: Vec<&str>) -> Hands {
         Hands { hands: lines.into_iter().map(Hand::from).collect() }
     }
 
-    pub fn total_winnings(&mut self) -> u32 {
-        self.hands.sort_by(|a, b| a.cmp(b));
-        self.hands.iter().enumerate().map(|(i, h)| (i + 1) as u32 * h.bid).sum()
+    pub fn total_winnings(&self) -> u32 {
+        self.total_winnings_with(&CardRules::standard())
+    }
+
+    pub fn total_winnings_with_jokers(&self) -> u32 {
+        self.total_winnings_with(&CardRules::jokers())
     }
 
-    pub fn total_winnings_with_jokers(&mut self) -> u32 {
-        self.hands.sort_by(|a, b| a.cmp_jokers(b));
-        self.hands.iter().enumerate().map(|(i, h)| (i + 1) as u32 * h.bid).sum()
+    pub fn total_winnings_with(&self, rules: &CardRules) -> u32 {
+        self.ranking(rules).iter().map(|r| r.contribution).sum()
+    }
+
+    // weakest hand first, each one explained against the hands just below and just above it
+    pub fn ranking(&self, rules: &CardRules) -> Vec<Ranked<'_>> {
+        let mut sorted: Vec<&Hand> = self.hands.iter().collect();
+        sorted.sort_by_cached_key(|hand| (rules.strength(hand.score_with(rules)), hand.cards_value(rules)));
+
+        sorted.iter().enumerate()
+            .map(|(i, hand)| {
+                let rank = (i + 1) as u32;
+                let below = match i {
+                    0 => "lowest hand".to_string(),
+                    _ => hand.explain_against(sorted[i - 1], rules),
+                };
+                let above = match sorted.get(i + 1) {
+                    Some(next) => hand.explain_against(next, rules),
+                    None => "highest hand".to_string(),
+                };
+                let explanation = format!("{below}; {above}");
+                Ranked { hand, hand_type: hand.score_with(rules), substitution: hand.substitution(rules), rank, contribution: rank * hand.bid, explanation }
+            })
+            .collect()
     }
 }
 
+#[derive(PartialEq, Debug)]
+struct Ranked<'a> {
+    hand: &'a Hand,
+    hand_type: HandType,
+    substitution: String,
+    rank: u32,
+    contribution: u32,
+    explanation: String,
+}
+
+pub fn ranking_report(input: &str, jokers: bool, json: bool) -> String {
+    let hands = Hands::from(input.lines().collect());
+    let rules = if jokers { CardRules::jokers() } else { CardRules::standard() };
+    let ranking = hands.ranking(&rules);
+
+    if json { to_json(&ranking) + "\n" } else { table(&ranking) }
+}
+
+fn table(ranking: &[Ranked]) -> String {
+    let mut table = format!("{:>4}  {:<8} {:<12} {:<8} {:>6} {:>10}  {}\n", "Rank", "Hand", "Type", "Played", "Bid", "Winnings", "Tie-break");
+    for r in ranking {
+        table += &format!("{:>4}  {:<8} {:<12} {:<8} {:>6} {:>10}  {}\n", r.rank, r.hand.cards, format!("{:?}", r.hand_type), r.substitution, r.hand.bid, r.contribution, r.explanation);
+    }
+    table + &format!("{:>4}  {:<8} {:<12} {:<8} {:>6} {:>10}\n", "", "total", "", "", "", ranking.iter().map(|r| r.contribution).sum::<u32>())
+}
+
+fn to_json(ranking: &[Ranked]) -> String {
+    let hands: Vec<_> = ranking.iter()
+        .map(|r| json!({
+            "rank": r.rank,
+            "hand": r.hand.cards,
+            "type": format!("{:?}", r.hand_type),
+            "played": r.substitution,
+            "bid": r.hand.bid,
+            "winnings": r.contribution,
+            "tie_break": r.explanation,
+        }))
+        .collect();
+
+    serde_json::to_string_pretty(&hands).unwrap()
+}
+
 impl Hand {
     pub fn new(cards: &str) -> Hand {
         Hand { cards: cards.to_string(), bid: 1 }
     }
 
     pub fn from(line: &str) -> Hand {
-        let mut parts = line.split_whitespace();
-        Hand { cards: parts.next().unwrap().to_string(), bid: parts.next().map(|p| p.parse().unwrap()).unwrap() }
+        let (cards, bid) = parse::key_value(line, " ").unwrap();
+        Hand { cards: cards.to_string(), bid: parse::number(bid).unwrap() }
     }
 
     pub fn cmp(&self, other: &Self) -> Ordering {
-        self.score().cmp(&other.score())
-            .then(self.cards_value(m1()).cmp(&other.cards_value(m1())))
+        self.cmp_with(other, &CardRules::standard())
     }
 
     pub fn cmp_jokers(&self, other: &Self) -> Ordering {
-        self.score_jokers().cmp(&other.score_jokers())
-            .then(self.cards_value(m2()).cmp(&other.cards_value(m2())))
+        self.cmp_with(other, &CardRules::jokers())
+    }
+
+    pub fn cmp_with(&self, other: &Self, rules: &CardRules) -> Ordering {
+        rules.strength(self.score_with(rules)).cmp(&rules.strength(other.score_with(rules)))
+            .then(self.cards_value(rules).cmp(&other.cards_value(rules)))
     }
 
     pub fn score(&self) -> HandType {
-        let cards_count = self.cards.chars().counts_by(|c| c);
-        Self::match_cards_count(cards_count)
+        self.score_with(&CardRules::standard())
     }
 
     pub fn score_jokers(&self) -> HandType {
+        self.score_with(&CardRules::jokers())
+    }
+
+    pub fn score_with(&self, rules: &CardRules) -> HandType {
         let mut cards_count = self.cards.chars().counts_by(|c| c);
-        Self::replace_wildcards(&mut cards_count);
-        Self::match_cards_count(cards_count)
+        assert_eq!(cards_count.values().sum::<usize>(), rules.hand_size, "hand {} has the wrong size", self.cards);
+
+        if self.has_wildcards(rules) {
+            Self::replace_wildcards(&mut cards_count, rules);
+        }
+        Self::match_cards_count(cards_count, rules.hand_size)
     }
 
-    fn replace_wildcards(cards_count: &mut HashMap<char, usize>) {
-        let jokers = cards_count.remove(&'J').unwrap_or(0);
-        let max_card = cards_count.iter().max_by_key(|e| e.1).map(|e| e.0).unwrap_or(&'J');
-        cards_count.entry(*max_card).and_modify(|v| { *v += jokers }).or_insert(5);
+    fn has_wildcards(&self, rules: &CardRules) -> bool {
+        self.cards.chars().any(|c| rules.wildcards.contains(&c))
     }
 
-    fn match_cards_count(cards_count: HashMap<char, usize>) -> HandType {
-        match cards_count.values().sorted().collect_vec().as_slice() {
-            [5] => HandType::FiveOfAKind,
-            [1, 4] => HandType::FourOfAKind,
-            [2, 3] => HandType::FullHouse,
-            [1, 1, 3] => HandType::ThreeOfAKind,
-            [1, 2, 2] => HandType::TwoPairs,
-            [1, 1, 1, 2] => HandType::OnePair,
-            _ => HandType::HighCard,
+    fn replace_wildcards(cards_count: &mut HashMap<char, usize>, rules: &CardRules) {
+        let substitutes = Self::best_substitutes(cards_count, rules);
+        cards_count.retain(|c, _| !rules.wildcards.contains(c));
+        substitutes.into_iter().for_each(|c| *cards_count.entry(c).or_insert(0) += 1);
+    }
+
+    // each wildcard plays as its own card, spread over the cards held, then the strongest cards not held,
+    // keeping the strongest hand type under the rules and, on equal types, the most on the cards held the most
+    fn best_substitutes(cards_count: &HashMap<char, usize>, rules: &CardRules) -> Vec<char> {
+        let wildcards: usize = rules.wildcards.iter().filter_map(|w| cards_count.get(w)).sum();
+        let held: HashMap<char, usize> = cards_count.iter()
+            .filter(|(c, _)| !rules.wildcards.contains(c))
+            .map(|(c, count)| (*c, *count))
+            .collect();
+        let candidates: Vec<char> = held.keys().copied()
+            .sorted_by_key(|c| Reverse((held[c], rules.rank(*c))))
+            .chain(rules.ranks.iter().rev().filter(|c| !rules.wildcards.contains(c) && !held.contains_key(c)).copied().take(wildcards))
+            .collect();
+        if candidates.is_empty() {
+            return vec![rules.wildcards[0]; wildcards];
         }
+
+        let mut best: Option<(usize, Vec<char>)> = None;
+        Self::spread(&candidates, wildcards, &mut vec![], &mut |substitutes| {
+            let mut counts = held.clone();
+            substitutes.iter().for_each(|c| *counts.entry(*c).or_insert(0) += 1);
+            let strength = rules.strength(Self::match_cards_count(counts, rules.hand_size));
+            if best.as_ref().is_none_or(|(best_strength, _)| strength > *best_strength) {
+                best = Some((strength, substitutes.to_vec()));
+            }
+        });
+        best.unwrap().1
     }
 
-    pub fn cards_value(&self, card_values_mapping: HashMap<char, u32>) -> Vec<u32> {
-        self.cards.chars().map(|c| *card_values_mapping.get(&c).unwrap()).collect()
+    // every way to hand out the wildcards left, the most to the first candidates first
+    fn spread(candidates: &[char], left: usize, chosen: &mut Vec<char>, visit: &mut impl FnMut(&[char])) {
+        if left == 0 {
+            return visit(chosen);
+        }
+        let Some((candidate, rest)) = candidates.split_first() else { return };
+
+        for k in (0..=left).rev() {
+            chosen.extend(std::iter::repeat_n(*candidate, k));
+            Self::spread(rest, left - k, chosen, visit);
+            chosen.truncate(chosen.len() - k);
+        }
     }
-}
 
-fn m1() -> HashMap<char, u32> {
-    HashMap::from([
-        ('2', 2), ('3', 3), ('4', 4), ('5', 5), ('6', 6), ('7', 7), ('8', 8), ('9', 9),
-        ('T', 10), ('J', 11), ('Q', 12), ('K', 13), ('A', 14)
-    ])
-}
+    pub fn substitution(&self, rules: &CardRules) -> String {
+        if !self.has_wildcards(rules) {
+            return self.cards.clone();
+        }
 
-fn m2() -> HashMap<char, u32> {
-    HashMap::from([
-        ('2', 2), ('3', 3), ('4', 4), ('5', 5), ('6', 6), ('7', 7), ('8', 8), ('9', 9),
-        ('T', 10), ('J', 0), ('Q', 12), ('K', 13), ('A', 14)
-    ])
+        let mut substitutes = Self::best_substitutes(&self.cards.chars().counts_by(|c| c), rules).into_iter();
+        self.cards.chars().map(|c| if rules.wildcards.contains(&c) { substitutes.next().unwrap() } else { c }).collect()
+    }
+
+    pub fn explain_against(&self, other: &Hand, rules: &CardRules) -> String {
+        let (verb, preposition) = match self.cmp_with(other, rules) {
+            Ordering::Greater => ("beats", "over"),
+            Ordering::Less => ("loses to", "under"),
+            Ordering::Equal => return format!("ties with {}", other.cards),
+        };
+
+        let (hand_type, other_type) = (self.score_with(rules), other.score_with(rules));
+        if hand_type != other_type {
+            return format!("{verb} {} with {:?} {preposition} {:?}", other.cards, hand_type, other_type);
+        }
+
+        let (i, (card, other_card)) = self.cards.chars().zip(other.cards.chars()).enumerate().find(|(_, (a, b))| a != b).unwrap();
+        format!("{verb} {} on card {}: {} {preposition} {}", other.cards, i + 1, card, other_card)
+    }
+
+    // five of a kind stands for all the cards being the same, whatever the hand size
+    fn match_cards_count(cards_count: HashMap<char, usize>, hand_size: usize) -> HandType {
+        match cards_count.values().sorted().rev().collect_vec().as_slice() {
+            [all, ..] if **all == hand_size => HandType::FiveOfAKind,
+            [four, ..] if **four >= 4 => HandType::FourOfAKind,
+            [3, pair, ..] if **pair >= 2 => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPairs,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+
+    pub fn cards_value(&self, rules: &CardRules) -> Vec<u32> {
+        self.cards.chars().map(|c| rules.rank(c)).collect()
+    }
 }
\ No newline at end of file
@@ -1,11 +1,13 @@
-use std::collections::HashMap;
-use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use itertools::Itertools;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
     use crate::daily::daily_input;
-    use crate::day08::Maps;
+    use std::collections::BTreeMap;
+    use crate::day08::{Ghost, Maps};
 
     const EXAMPLE_INPUT: &str = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
     const EXAMPLE_INPUT_2: &str = "LR\n\n 11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)";
@@ -61,7 +63,82 @@ mod tests {
     fn solves_example_part2() {
         let map = Maps::from(EXAMPLE_INPUT_2);
 
-        assert_eq!(map.steps_to_reach_the_end_in_parallel(), 6)
+        assert_eq!(map.steps_to_reach_the_end_in_parallel(), Some(6))
+    }
+
+    #[test]
+    fn detects_ghost_cycles() {
+        let map = Maps::from(EXAMPLE_INPUT_2);
+
+        assert_eq!(map.ghost("11A"), Ghost { prefix: vec![], start: 1, period: 2, offsets: vec![2] });
+        assert_eq!(map.ghost("22A"), Ghost { prefix: vec![], start: 1, period: 6, offsets: vec![3, 6] });
+    }
+
+    #[test]
+    fn combines_congruences() {
+        assert_eq!(Maps::crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(Maps::crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(Maps::crt((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn aligns_ghosts_not_arriving_at_their_period() {
+        let map = Maps::from("L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11C, 11C)\n11C = (11Z, 11Z)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22B, 22B)");
+
+        assert_eq!(map.steps_to_reach_the_end_in_parallel(), Some(4))
+    }
+
+    #[test]
+    fn aligns_a_lone_ghost_on_its_first_arrival() {
+        let map = Maps::from("LR\n\n22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)");
+
+        assert_eq!(map.steps_to_reach_the_end_in_parallel(), Some(3))
+    }
+
+    #[test]
+    fn reports_ghosts_never_aligning() {
+        let map = Maps::from("L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22B, 22B)");
+
+        assert_eq!(map.steps_to_reach_the_end_in_parallel(), None)
+    }
+
+    const DISCONNECTED_INPUT: &str = "LR\n\nAAA = (BBB, BBB)\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\nCCC = (ZZZ, DDD)\nDDD = (DDD, DDD)\nEEE = (EEE, EEE)";
+
+    #[test]
+    fn exports_network_to_dot() {
+        let map = Maps::from(EXAMPLE_INPUT);
+
+        assert_eq!(map.to_dot(), "\
+digraph network {
+    \"AAA\" [color=green];
+    \"AAA\" -> \"BBB\" [label=L];
+    \"AAA\" -> \"BBB\" [label=R];
+    \"BBB\" -> \"AAA\" [label=L];
+    \"BBB\" -> \"ZZZ\" [label=R];
+    \"ZZZ\" [color=red];
+    \"ZZZ\" -> \"ZZZ\" [label=L];
+    \"ZZZ\" -> \"ZZZ\" [label=R];
+}
+");
+    }
+
+    #[test]
+    fn finds_connected_components() {
+        assert_eq!(Maps::from(EXAMPLE_INPUT_2).components(), vec![vec!["11A", "11B", "11Z", "22A", "22B", "22C", "22Z", "XXX"]]);
+        assert_eq!(Maps::from(DISCONNECTED_INPUT).components(), vec![vec!["AAA", "BBB", "CCC", "DDD", "ZZZ"], vec!["EEE"]]);
+    }
+
+    #[test]
+    fn finds_ends_reachable_from_each_start() {
+        let map = Maps::from(EXAMPLE_INPUT_2);
+
+        assert_eq!(map.reachable_ends(), BTreeMap::from([("11A", vec!["11Z"]), ("22A", vec!["22Z"])]));
+    }
+
+    #[test]
+    fn reports_unreachable_nodes() {
+        assert!(Maps::from(EXAMPLE_INPUT_2).unreachable_nodes().is_empty());
+        assert_eq!(Maps::from(DISCONNECTED_INPUT).unreachable_nodes(), vec!["CCC", "DDD", "EEE"]);
     }
 
     #[test]
@@ -69,10 +146,18 @@ mod tests {
         let input = daily_input(8);
         let map = Maps::from(&input);
 
-        assert_eq!(map.steps_to_reach_the_end_in_parallel(), 16187743689077)
+        assert_eq!(map.steps_to_reach_the_end_in_parallel(), Some(16187743689077))
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Maps::from(input).steps_to_reach_the_end_simple().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Maps::from(input).steps_to_reach_the_end_in_parallel().unwrap().to_string()
+}
+
 struct Maps {
     instructions: String,
     nodes: HashMap<String, Node>,
@@ -84,15 +169,30 @@ struct Node {
     right: String,
 }
 
+// steps at which a ghost stands on a Z node: the prefix ones once, the offsets every period
+#[derive(PartialEq, Debug)]
+struct Ghost {
+    prefix: Vec<usize>,
+    start: usize,
+    period: usize,
+    offsets: Vec<usize>,
+}
+
+impl Ghost {
+    fn arrives_at(&self, steps: usize) -> bool {
+        self.prefix.contains(&steps) || self.offsets.iter().any(|&offset| steps >= offset && (steps - offset).is_multiple_of(self.period))
+    }
+}
+
 impl Maps {
     pub fn from(input: &str) -> Maps {
-        let mut parts = input.split("\n\n");
-        let instructions = parts.next().unwrap().to_string();
+        let blocks = parse::blocks(input);
+        let instructions = blocks[0].to_string();
 
         let mut nodes = HashMap::new();
-        for line in parts.next().unwrap().split('\n') {
-            let regex = Regex::new(r"(?<id>[A-Z0-9]+) = \((?<left>[A-Z0-9]+), (?<right>[A-Z0-9]+)\)").unwrap();
-            let (_, [id, left, right]) = regex.captures(line).map(|g| g.extract()).unwrap();
+        for line in blocks[1].lines() {
+            let (id, next) = parse::key_value(line, "=").unwrap();
+            let (left, right) = parse::key_value(parse::delimited(next, '(', ')').unwrap(), ",").unwrap();
 
             nodes.insert(id.to_string(), Node { left: left.to_string(), right: right.to_string() });
         }
@@ -104,10 +204,75 @@ impl Maps {
         self.steps_to_reach_the_end("AAA")
     }
 
-    pub fn steps_to_reach_the_end_in_parallel(&self) -> usize {
-        self.starting_nodes().iter()
-            .map(|starting_node| self.steps_to_reach_the_end(starting_node))
-            .fold(1, Self::lcm)
+    // None when the ghosts never all stand on a Z node at the same time
+    pub fn steps_to_reach_the_end_in_parallel(&self) -> Option<usize> {
+        let ghosts: Vec<Ghost> = self.starting_nodes().iter().map(|starting_node| self.ghost(starting_node)).collect();
+
+        // before every ghost is in its cycle, only the Z arrivals of the last one to get there can match
+        let last_in = ghosts.iter().max_by_key(|g| g.start)?;
+        if let Some(steps) = last_in.prefix.iter().find(|&&steps| ghosts.iter().all(|g| g.arrives_at(steps))) {
+            return Some(*steps);
+        }
+
+        let mut congruences: Vec<(i128, i128)> = vec![(0, 1)];
+        for ghost in ghosts.iter() {
+            congruences = congruences.iter()
+                .flat_map(|&congruence| ghost.offsets.iter().filter_map(move |&offset| Self::crt(congruence, (offset as i128, ghost.period as i128))))
+                .collect();
+        }
+
+        let in_cycles = last_in.start as i128;
+        congruences.iter()
+            .map(|(residue, modulus)| residue + (in_cycles - residue + modulus - 1).max(0) / modulus * modulus)
+            .min()
+            .map(|steps| steps as usize)
+    }
+
+    // walks until a (node, instruction index) state repeats
+    fn ghost(&self, starting_node: &str) -> Ghost {
+        let instructions = self.instructions.as_bytes();
+        let mut visited: HashMap<(&str, usize), usize> = HashMap::new();
+        let mut arrivals = vec![];
+        let mut current_node = starting_node;
+
+        for steps in 0.. {
+            let state = (current_node, steps % instructions.len());
+            if let Some(&start) = visited.get(&state) {
+                let (prefix, offsets) = arrivals.into_iter().partition(|&arrival| arrival < start);
+                return Ghost { prefix, start, period: steps - start, offsets };
+            }
+            visited.insert(state, steps);
+
+            if current_node.ends_with('Z') {
+                arrivals.push(steps);
+            }
+            let next_node = self.nodes.get(current_node).unwrap();
+            current_node = match instructions[state.1] {
+                b'L' => &next_node.left,
+                _ => &next_node.right,
+            };
+        }
+        unreachable!()
+    }
+
+    // x = a1 mod m1 and x = a2 mod m2, moduli don't need to be coprime
+    fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+        let (g, p, _) = Self::extended_gcd(m1, m2);
+        if (a2 - a1) % g != 0 {
+            return None;
+        }
+
+        let lcm = m1 / g * m2;
+        let x = (a1 + (a2 - a1) / g % (m2 / g) * p % (m2 / g) * m1).rem_euclid(lcm);
+        Some((x, lcm))
+    }
+
+    fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+        if b == 0 {
+            return (a, 1, 0);
+        }
+        let (g, x, y) = Self::extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
     }
 
     fn steps_to_reach_the_end(&self, starting_node: &str) -> usize {
@@ -127,6 +292,73 @@ impl Maps {
         steps
     }
 
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n");
+        for (id, node) in self.nodes.iter().sorted_by_key(|(id, _)| *id) {
+            if id.ends_with('A') || id.ends_with('Z') {
+                dot += &format!("    \"{id}\" [color={}];\n", if id.ends_with('A') { "green" } else { "red" });
+            }
+            dot += &format!("    \"{id}\" -> \"{}\" [label=L];\n", node.left);
+            dot += &format!("    \"{id}\" -> \"{}\" [label=R];\n", node.right);
+        }
+        dot + "}\n"
+    }
+
+    // weakly connected: the direction of left and right is ignored
+    pub fn components(&self) -> Vec<Vec<&str>> {
+        let mut neighbours: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (id, node) in self.nodes.iter() {
+            for next in [&node.left, &node.right] {
+                neighbours.entry(id).or_default().push(next);
+                neighbours.entry(next).or_default().push(id);
+            }
+        }
+
+        let mut components = vec![];
+        let mut visited = HashSet::new();
+        for id in neighbours.keys().sorted() {
+            if visited.contains(id) { continue; }
+
+            let component: Vec<&str> = Self::walk(id, |n| neighbours[n].clone()).into_iter().collect();
+            visited.extend(component.iter().copied());
+            components.push(component);
+        }
+        components
+    }
+
+    pub fn reachable_from<'a>(&'a self, starting_node: &'a str) -> BTreeSet<&'a str> {
+        Self::walk(starting_node, |id| self.nodes.get(id).map(|n| vec![n.left.as_str(), n.right.as_str()]).unwrap_or_default())
+    }
+
+    pub fn reachable_ends(&self) -> BTreeMap<&str, Vec<&str>> {
+        self.starting_nodes().into_iter()
+            .map(|starting_node| {
+                let ends = self.reachable_from(starting_node).into_iter().filter(|id| id.ends_with('Z')).collect();
+                (starting_node.as_str(), ends)
+            })
+            .collect()
+    }
+
+    // nodes no starting node leads to, whatever the instructions: following left and right alike,
+    // so a ghost walking the actual instructions can miss more of them
+    pub fn unreachable_nodes(&self) -> Vec<&str> {
+        let reachable: BTreeSet<&str> = self.starting_nodes().into_iter().flat_map(|n| self.reachable_from(n)).collect();
+        self.nodes.keys().map(|id| id.as_str()).filter(|id| !reachable.contains(id)).sorted().collect()
+    }
+
+    fn walk<'a>(from: &'a str, next: impl Fn(&'a str) -> Vec<&'a str>) -> BTreeSet<&'a str> {
+        let mut visited = BTreeSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            for n in next(id) {
+                if visited.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+        visited
+    }
+
     fn starting_nodes(&self) -> Vec<&String> {
         self.nodes.keys().filter(|id| id.ends_with('A')).collect()
     }
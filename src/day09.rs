@@ -1,5 +1,9 @@
+use std::fmt;
+use crate::parse;
+
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use crate::daily::daily_input;
     use super::*;
 
@@ -14,16 +18,16 @@ mod tests {
 
     #[test]
     fn finds_extrapolated_value() {
-        assert_eq!(History::from("0 3 6 9 12 15").extrapolated_value(), 18);
-        assert_eq!(History::from("1 3 6 10 15 21").extrapolated_value(), 28);
-        assert_eq!(History::from("10 13 16 21 30 45").extrapolated_value(), 68);
+        assert_eq!(History::from("0 3 6 9 12 15").extrapolated_value(), Ok(18));
+        assert_eq!(History::from("1 3 6 10 15 21").extrapolated_value(), Ok(28));
+        assert_eq!(History::from("10 13 16 21 30 45").extrapolated_value(), Ok(68));
     }
 
     #[test]
     fn solves_example_part1() {
         let histories = Histories::from(EXAMPLE_INPUT);
 
-        assert_eq!(histories.sum_of_extrapolated_values(), 114);
+        assert_eq!(histories.sum_of_extrapolated_values(), Ok(114));
     }
 
     #[test]
@@ -31,21 +35,91 @@ mod tests {
         let input = daily_input(9);
         let histories = Histories::from(&input);
 
-        assert_eq!(histories.sum_of_extrapolated_values(), 1806615041);
+        assert_eq!(histories.sum_of_extrapolated_values(), Ok(1806615041));
     }
 
     #[test]
     fn finds_previous_extrapolated_value() {
-        assert_eq!(History::from("0 3 6 9 12 15").previous_extrapolated_value(), -3);
-        assert_eq!(History::from("1 3 6 10 15 21").previous_extrapolated_value(), 0);
-        assert_eq!(History::from("10 13 16 21 30 45").previous_extrapolated_value(), 5);
+        assert_eq!(History::from("0 3 6 9 12 15").previous_extrapolated_value(), Ok(-3));
+        assert_eq!(History::from("1 3 6 10 15 21").previous_extrapolated_value(), Ok(0));
+        assert_eq!(History::from("10 13 16 21 30 45").previous_extrapolated_value(), Ok(5));
+    }
+
+    #[test]
+    fn derives_newton_polynomial() {
+        let polynomial = History::from("1 3 6 10 15 21").polynomial().unwrap();
+
+        assert_eq!(polynomial.coefficients(), Ok(vec![Rational::from(1), Rational::from(2), Rational::new(1, 2)]));
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(polynomial.evaluate(1000), Ok(1001 * 1002 / 2));
+    }
+
+    #[test]
+    fn finds_values_at_any_index() {
+        let history = History::from("10 13 16 21 30 45");
+
+        assert_eq!(history.value_at(6), Ok(68));
+        assert_eq!(history.value_at(-1), Ok(5));
+        assert_eq!(history.value_at(2), Ok(16));
+        assert_eq!(history.value_at(-20), Ok(-3130));
+    }
+
+    #[test]
+    fn evaluates_high_degree_polynomials_without_overflow() {
+        let values: Vec<i128> = (0..21).map(|i: i128| (i * i * 7919 + i * 31) % 1000).collect();
+        let history = History::from(&values.iter().join(" "));
+
+        assert_eq!(history.polynomial().unwrap().degree(), 20);
+        assert!((0..21).all(|i| history.value_at(i) == Ok(values[i as usize])));
+
+        let (mut differences, mut next_value) = (values.clone(), 0);
+        while let Some(last) = differences.last() {
+            next_value += last;
+            differences = differences.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+        assert_eq!(history.extrapolated_value(), Ok(next_value));
+    }
+
+    #[test]
+    fn detects_sequences_that_are_not_polynomial() {
+        assert_eq!(History::from("0 3 6 9 12 15").is_polynomial(), Ok(true));
+        assert_eq!(History::from("7 7").is_polynomial(), Ok(true));
+        assert_eq!(History::from("1 2 4 8").is_polynomial(), Ok(false));
+        assert_eq!(History::from("1 2 4 8").polynomial().unwrap().degree(), 3);
+        assert_eq!(History::from("").extrapolated_value(), Ok(0));
+    }
+
+    #[test]
+    fn reports_overflows() {
+        let history = History::from(&format!("{} {}", i128::MAX, i128::MIN));
+
+        assert_eq!(history.polynomial(), Err("difference overflows i128".to_string()));
+        assert_eq!(History::from("1 3 6 10 15 21").value_at(10_i128.pow(30)), Err("value overflows i128".to_string()));
+        assert_eq!(Histories::from(&format!("{} {}\n0 1", i128::MAX - 1, i128::MAX)).sum_of_extrapolated_values(), Err("value overflows i128".to_string()));
+        assert_eq!(History::from("1 3 6").value_at(i128::MIN), Err("value overflows i128".to_string()));
+    }
+
+    #[test]
+    fn evaluates_binomials_close_to_the_limit() {
+        let x = 1_i128 << 64;
+
+        assert_eq!(History::from("0 0 1 3").value_at(x), Ok(x / 2 * (x - 1)));
+    }
+
+    #[test]
+    fn extrapolates_long_histories() {
+        let values: Vec<String> = (0..5000_i128).map(|i| (i * i * 1_000_000_000_000).to_string()).collect();
+        let history = History::from(&values.join(" "));
+
+        assert_eq!(history.polynomial().unwrap().degree(), 2);
+        assert_eq!(history.extrapolated_value(), Ok(5000 * 5000 * 1_000_000_000_000));
     }
 
     #[test]
     fn solves_example_part2() {
         let histories = Histories::from(EXAMPLE_INPUT);
 
-        assert_eq!(histories.sum_of_previous_extrapolated_values(), 2);
+        assert_eq!(histories.sum_of_previous_extrapolated_values(), Ok(2));
     }
 
     #[test]
@@ -53,61 +127,151 @@ mod tests {
         let input = daily_input(9);
         let histories = Histories::from(&input);
 
-        assert_eq!(histories.sum_of_previous_extrapolated_values(), 1211);
+        assert_eq!(histories.sum_of_previous_extrapolated_values(), Ok(1211));
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Histories::from(input).sum_of_extrapolated_values().unwrap().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Histories::from(input).sum_of_previous_extrapolated_values().unwrap().to_string()
+}
+
 struct Histories {
     histories: Vec<History>
 }
 
 struct History {
-    values: Vec<i32>
+    values: Vec<i128>
+}
+
+// forward differences of the history, the Newton form being their sum times C(x, k)
+#[derive(PartialEq, Debug)]
+struct Polynomial {
+    differences: Vec<i128>,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Rational {
+    numerator: i128,
+    denominator: i128,
 }
 
 impl Histories {
-    pub fn from(_input: &str) -> Histories {
-        Histories { histories: _input.split('\n').map(History::from).collect() }
+    pub fn from(input: &str) -> Histories {
+        Histories { histories: input.split('\n').map(History::from).collect() }
     }
 
-    pub fn sum_of_extrapolated_values(&self) -> i32 {
-        self.histories.iter().map(|h| h.extrapolated_value()).sum()
+    pub fn sum_of_extrapolated_values(&self) -> Result<i128, String> {
+        self.histories.iter().try_fold(0, |sum, h| Self::checked_add(sum, h.extrapolated_value()?))
     }
 
-    pub fn sum_of_previous_extrapolated_values(&self) -> i32 {
-        self.histories.iter().map(|h| h.previous_extrapolated_value()).sum()
+    pub fn sum_of_previous_extrapolated_values(&self) -> Result<i128, String> {
+        self.histories.iter().try_fold(0, |sum, h| Self::checked_add(sum, h.previous_extrapolated_value()?))
+    }
+
+    fn checked_add(sum: i128, value: i128) -> Result<i128, String> {
+        sum.checked_add(value).ok_or("value overflows i128".to_string())
     }
 }
 
 impl History {
     pub fn from(input: &str) -> History {
-        History { values: input.split_whitespace().map(|v| v.parse().unwrap()).collect() }
+        History { values: parse::numbers(input).unwrap() }
+    }
+
+    pub fn extrapolated_value(&self) -> Result<i128, String> {
+        self.value_at(self.values.len() as i128)
     }
 
-    pub fn extrapolated_value(&self) -> i32 {
-        Self::extrapolate(&self.values)
+    pub fn previous_extrapolated_value(&self) -> Result<i128, String> {
+        self.value_at(-1)
     }
 
-    pub fn previous_extrapolated_value(&self) -> i32 {
-        let mut reversed_values = self.values.to_vec();
-        reversed_values.reverse();
-        Self::extrapolate(&reversed_values)
+    pub fn value_at(&self, index: i128) -> Result<i128, String> {
+        self.polynomial()?.evaluate(index)
     }
 
-    fn extrapolate(values: &[i32]) -> i32 {
-        let mut current_values: Vec<i32> = values.to_vec();
-        let mut last_values = vec![];
+    // stops at the first row of zeros, the differences after it being zeros as well
+    pub fn polynomial(&self) -> Result<Polynomial, String> {
+        let mut row = self.values.clone();
+        let mut differences = vec![];
+
+        while let Some(&first) = row.first() {
+            differences.push(first);
+            if row.iter().all(|v| *v == 0) { break; }
 
-        while !current_values.iter().all(|v| *v == 0) {
-            let mut next_values = vec![];
-            let last_value = current_values.pop().unwrap();
-            for (i,v) in current_values.iter().enumerate() {
-                let next_v = current_values.get(i + 1).unwrap_or(&last_value);
-                next_values.push(next_v - v)
+            row = row.windows(2)
+                .map(|w| w[1].checked_sub(w[0]).ok_or("difference overflows i128".to_string()))
+                .collect::<Result<_, _>>()?;
+        }
+
+        Ok(Polynomial { differences })
+    }
+
+    // at least one value more than the degree needs, confirming the last difference is constant
+    pub fn is_polynomial(&self) -> Result<bool, String> {
+        Ok(self.polynomial()?.degree() + 1 < self.values.len())
+    }
+}
+
+impl Polynomial {
+    pub fn degree(&self) -> usize {
+        self.differences.iter().rposition(|d| *d != 0).unwrap_or(0)
+    }
+
+    // the k-th coefficient is the k-th forward difference over k!
+    pub fn coefficients(&self) -> Result<Vec<Rational>, String> {
+        let mut factorial: i128 = 1;
+        (0..=self.degree() as i128)
+            .map(|k| {
+                factorial = factorial.checked_mul(k.max(1)).ok_or("coefficient overflows i128".to_string())?;
+                Ok(Rational::new(self.differences[k as usize], factorial))
+            })
+            .collect()
+    }
+
+    // a polynomial through integer points takes integer values at every integer index,
+    // C(x, k) = C(x, k - 1) * (x - k + 1) / k with the division done first so only a binomial too large overflows
+    pub fn evaluate(&self, x: i128) -> Result<i128, String> {
+        let overflow = || "value overflows i128".to_string();
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+
+        for (k, difference) in self.differences.iter().take(self.degree() + 1).enumerate().map(|(k, d)| (k as i128, d)) {
+            if k > 0 {
+                let factor = x.checked_sub(k - 1).ok_or_else(overflow)?;
+                let gcd = Rational::gcd(binomial, k);
+                binomial = (binomial / gcd).checked_mul(factor / (k / gcd)).ok_or_else(overflow)?;
             }
-            current_values = next_values;
-            last_values.push(last_value);
+            value = difference.checked_mul(binomial).and_then(|term| value.checked_add(term)).ok_or_else(overflow)?;
+        }
+        Ok(value)
+    }
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        let gcd = Self::gcd(numerator, denominator).max(1) * denominator.signum();
+        Rational { numerator: numerator / gcd, denominator: denominator / gcd }
+    }
+
+    pub fn from(integer: i128) -> Rational {
+        Rational { numerator: integer, denominator: 1 }
+    }
+
+    fn gcd(a: i128, b: i128) -> i128 {
+        if b == 0 { a.abs() } else { Self::gcd(b, a % b) }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
         }
-        last_values.iter().sum()
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,10 @@
 use Direction::{North, South, East, West};
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
-    use crate::day10::{Direction, Position, Sketch, Tile};
+    use crate::day10::{Direction, Sketch, Tile};
     use Direction::{North, South, East, West};
 
     const EXAMPLE_1: &str = "\
@@ -23,66 +24,97 @@ L|-JF";
     }
 
     #[test]
-    fn follows_vertical_pipes() {
-        let vertical_pipe = Tile::new('|');
-        assert_eq!(vertical_pipe.follow(&Position::new(11, North, 5)), Position::new(6, North, 5));
-        assert_eq!(vertical_pipe.follow(&Position::new(11, South, 5)), Position::new(16, South, 5));
+    fn lists_pipe_exits() {
+        assert_eq!(Tile::new('|').exits(), vec![North, South]);
+        assert_eq!(Tile::new('L').exits(), vec![North, East]);
+        assert_eq!(Tile::new('7').exits(), vec![South, West]);
+        assert_eq!(Tile::new('.').exits(), vec![]);
     }
 
     #[test]
-    fn follows_horizontal_pipes() {
-        let horizontal_pipe = Tile::new('-');
-        assert_eq!(horizontal_pipe.follow(&Position::new(7, East, 5)), Position::new(8, East, 5));
-        assert_eq!(horizontal_pipe.follow(&Position::new(7, West, 5)), Position::new(6, West, 5));
+    fn steps_to_neighbours_inside_the_field() {
+        let sketch = Sketch::from(EXAMPLE_1);
+
+        assert_eq!(sketch.neighbour(6, North), Some(1));
+        assert_eq!(sketch.neighbour(6, East), Some(7));
+        assert_eq!(sketch.neighbour(4, East), None);
+        assert_eq!(sketch.neighbour(5, West), None);
+        assert_eq!(sketch.neighbour(22, South), None);
+    }
+
+    const TWO_LOOPS: &str = "\
+....-
+.S-7.
+.|.|.
+.L-J.
+.....
+F--7.
+|..|.
+L--J.";
+
+    #[test]
+    fn infers_start_tile() {
+        assert_eq!(Sketch::from(EXAMPLE_1).start_tile(), Some(Tile::new('F')));
+        assert_eq!(Sketch::from("7-J\nLS7\n|.L").start_tile(), Some(Tile::new('-')));
+        assert_eq!(Sketch::from(".|.\n-S-\n...").start_tile(), None);
+    }
+
+    #[test]
+    fn finds_every_loop() {
+        let loops = Sketch::from(TWO_LOOPS).loops();
+
+        let measures: Vec<(u32, u32, (usize, usize), u32)> = loops.iter().map(|l| (l.length(), l.farthest_steps(), l.farthest_point, l.area)).collect();
+        assert_eq!(measures, vec![(8, 4, (3, 3), 1), (10, 5, (3, 7), 2)]);
     }
 
     #[test]
-    fn follows_north_to_est_pipes() {
-        let south_to_west_pipe = Tile::new('L');
-        assert_eq!(south_to_west_pipe.follow(&Position::new(16, South, 5)), Position::new(17, East, 5));
-        assert_eq!(south_to_west_pipe.follow(&Position::new(16, West, 5)), Position::new(11, North, 5));
+    fn resolves_stray_pipes_pointing_at_start() {
+        let sketch = Sketch::from(".|.\nFS.\nLJ.");
+
+        assert_eq!(sketch.start_tile(), Some(Tile::new('7')));
+        assert_eq!(sketch.steps_to_reach_farthest_point(), Ok(2));
+        assert_eq!(sketch.enclosed_tiles(), Ok(0));
     }
 
     #[test]
-    fn follows_north_to_west_pipes() {
-        let south_to_west_pipe = Tile::new('J');
-        assert_eq!(south_to_west_pipe.follow(&Position::new(18, South, 5)), Position::new(17, West, 5));
-        assert_eq!(south_to_west_pipe.follow(&Position::new(18, East, 5)), Position::new(13, North, 5));
+    fn reports_sketches_without_a_loop_through_start() {
+        assert_eq!(Sketch::from("F-7\n|.|\nL-J").steps_to_reach_farthest_point(), Err("no S in the sketch".to_string()));
+        assert_eq!(Sketch::from(".|.\n-S-\n...").enclosed_tiles(), Err("no loop through S".to_string()));
     }
 
     #[test]
-    fn follows_south_to_west_pipes() {
-        let south_to_west_pipe = Tile { tile: '7' };
-        assert_eq!(south_to_west_pipe.follow(&Position::new(8, East, 5)), Position::new(13, South, 5));
-        assert_eq!(south_to_west_pipe.follow(&Position::new(8, North, 5)), Position::new(7, West, 5));
+    fn walks_long_dead_ends_once() {
+        let sketch = Sketch::from(&vec!["|"; 20_000].join("\n"));
+
+        assert!(sketch.loops().is_empty());
     }
 
     #[test]
-    fn follows_south_to_east_pipes() {
-        let south_to_east_pipe = Tile { tile: 'F' };
-        assert_eq!(south_to_east_pipe.follow(&Position::new(6, West, 5)), Position::new(11, South, 5));
-        assert_eq!(south_to_east_pipe.follow(&Position::new(6, North, 5)), Position::new(7, East, 5));
+    fn ignores_pipes_off_the_edges() {
+        let sketch = Sketch::from("-7.\nFJ.\nL-7");
+
+        assert!(sketch.loops().is_empty());
     }
 
     #[test]
     fn solve_examples_part1() {
         let sketch = Sketch::from(EXAMPLE_1);
 
-        assert_eq!(sketch.steps_to_reach_farthest_point(), 4);
+        assert_eq!(sketch.steps_to_reach_farthest_point(), Ok(4));
     }
 
     #[test]
     fn solve_part1() {
         let sketch = Sketch::from(&daily_input(10));
 
-        assert_eq!(sketch.steps_to_reach_farthest_point(), 6864);
+        assert_eq!(sketch.steps_to_reach_farthest_point(), Ok(6864));
     }
 
     #[test]
     fn solve_examples_part2() {
         let sketch = Sketch::from(EXAMPLE_1);
 
-        assert_eq!(sketch.enclosed_tiles(), 1);
+        assert_eq!(sketch.enclosed_tiles(), Ok(1));
     }
 
 
@@ -90,106 +122,198 @@ L|-JF";
     fn solves_part2() {
         let sketch = Sketch::from(&daily_input(10));
 
-        assert_eq!(sketch.enclosed_tiles(), 349);
+        assert_eq!(sketch.enclosed_tiles(), Ok(349));
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Sketch::from(input).steps_to_reach_farthest_point().unwrap().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Sketch::from(input).enclosed_tiles().unwrap().to_string()
+}
+
 struct Sketch {
     tiles: Vec<Tile>,
     length: usize,
+    start_tile: Option<Tile>,
 }
 
 impl Sketch {
     pub fn from(input: &str) -> Sketch {
-        let length = input.find('\n').unwrap();
+        let length = input.find('\n').unwrap_or(input.len());
 
-        let tiles = input.chars()
-            .filter(|c| *c != '\n')
-            .map(|tile| Tile { tile })
+        let tiles = parse::grid(input)
+            .map(|(_, _, tile)| Tile { tile })
             .collect();
 
-        Sketch { tiles, length }
+        let mut sketch = Sketch { tiles, length, start_tile: None };
+        sketch.start_tile = sketch.infer_start_tile();
+        sketch
     }
 
-    pub fn steps_to_reach_farthest_point(&self) -> u32 {
-        self.find_loop().0
+    pub fn steps_to_reach_farthest_point(&self) -> Result<u32, String> {
+        Ok(self.main_loop()?.farthest_steps())
     }
 
-    pub fn enclosed_tiles(&self) -> u32 {
-        self.find_loop().1
+    pub fn enclosed_tiles(&self) -> Result<u32, String> {
+        Ok(self.main_loop()?.area)
     }
 
-    fn find_loop(&self) -> (u32, u32) {
-        let start = self.tiles.iter().position(|t| t.tile == 'S').unwrap();
+    fn main_loop(&self) -> Result<Loop, String> {
+        let start = self.start().ok_or("no S in the sketch")?;
+        self.loops().into_iter().find(|l| l.tiles[0] == start).ok_or("no loop through S".to_string())
+    }
 
-        let mut corner = Position::new(start, East, self.length);
-        let mut position = Position::new(start + 1, East, self.length);
-        let mut steps = 1;
-        let mut area: i32 = 0;
-        while self.tiles.get(position.index).unwrap().tile != 'S' {
-            let tile = self.tiles.get(position.index).unwrap();
-            if tile.tile == '7' || tile.tile == 'J' || tile.tile == 'F' || tile.tile == 'L' {
-                area += Self::shoelace(&corner, &position);
-                corner = position;
-            }
-            position = tile.follow(&position);
-            steps += 1;
-        }
+    fn start(&self) -> Option<usize> {
+        self.tiles.iter().position(|t| t.tile == 'S')
+    }
 
-        area += Self::shoelace(&corner, &position);
-        ((steps / 2) as u32, (area / 2 - steps / 2 + 1) as u32)
+    // the pipe hidden under S, None when no pipe fits
+    pub fn start_tile(&self) -> Option<Tile> {
+        self.start_tile.clone()
     }
 
-    fn shoelace(a: &Position, b: &Position) -> i32 {
-        ((a.index % a.length) * (b.index / b.length)) as i32 - ((b.index % b.length) * (a.index / a.length)) as i32
+    // the pipe connecting S to exactly two of its neighbours, or when stray pipes point at S as well,
+    // the first pipe towards them that closes a loop
+    fn infer_start_tile(&mut self) -> Option<Tile> {
+        let start = self.start()?;
+        let connected: Vec<Direction> = [North, East, South, West].into_iter()
+            .filter(|&d| self.neighbour(start, d).is_some_and(|n| self.tiles[n].exits().contains(&d.opposite())))
+            .collect();
+
+        let candidates: Vec<Tile> = PIPES.iter()
+            .map(|&pipe| Tile::new(pipe))
+            .filter(|t| t.exits().iter().all(|d| connected.contains(d)))
+            .collect();
+        if let [only] = candidates.as_slice() {
+            return Some(only.clone());
+        }
+
+        let visited = vec![false; self.tiles.len()];
+        candidates.into_iter().find(|candidate| {
+            self.start_tile = Some(candidate.clone());
+            self.trace(start, &visited).is_ok()
+        })
     }
-}
 
-#[derive(PartialEq, Debug)]
-struct Tile {
-    tile: char,
-}
+    fn exits(&self, index: usize) -> Vec<Direction> {
+        match self.tiles[index].tile {
+            'S' => self.start_tile.as_ref().map(|t| t.exits()).unwrap_or_default(),
+            _ => self.tiles[index].exits(),
+        }
+    }
 
-impl Tile {
-    pub fn new(tile: char) -> Tile {
-        Tile { tile }
+    fn neighbour(&self, index: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = (index % self.length, index / self.length);
+        let neighbour = match direction {
+            North if y > 0 => index - self.length,
+            South => index + self.length,
+            West if x > 0 => index - 1,
+            East if x + 1 < self.length => index + 1,
+            _ => return None,
+        };
+        (neighbour < self.tiles.len()).then_some(neighbour)
     }
 
-    pub fn follow(&self, position: &Position) -> Position {
-        match (self.tile, &position.direction) {
-            ('|', North) => Position::new(position.index - position.length, North, position.length),
-            ('|', South) => Position::new(position.index + position.length, South, position.length),
+    // the loop through S first, then the others in reading order,
+    // every tile is walked once: a walk stops at the tiles of the loops and dead ends already found
+    pub fn loops(&self) -> Vec<Loop> {
+        let mut loops: Vec<Loop> = vec![];
+        let mut visited = vec![false; self.tiles.len()];
+
+        for index in self.start().into_iter().chain(0..self.tiles.len()) {
+            if visited[index] { continue; }
+
+            match self.trace(index, &visited) {
+                Ok(tiles) => {
+                    tiles.iter().for_each(|&t| visited[t] = true);
+                    loops.push(self.measure(tiles));
+                }
+                Err(tiles) => tiles.iter().for_each(|&t| visited[t] = true),
+            }
+        }
+        loops
+    }
 
-            ('-', West) => Position::new(position.index - 1, West, position.length),
-            ('-', East) => Position::new(position.index + 1, East, position.length),
+    // follows the pipes, each one connected back, until coming back to where it started,
+    // the tiles walked are the error when the pipes lead to a dead end or to a visited tile
+    fn trace(&self, from: usize, visited: &[bool]) -> Result<Vec<usize>, Vec<usize>> {
+        let mut tiles = vec![from];
+        let Some(&first) = self.exits(from).first() else { return Err(tiles) };
+        let (mut index, mut direction) = (from, first);
+
+        loop {
+            let next = self.neighbour(index, direction).filter(|&n| !visited[n]);
+            let exits = next.map(|n| self.exits(n)).unwrap_or_default();
+            let Some(next) = next.filter(|_| exits.contains(&direction.opposite())) else { return Err(tiles) };
+            if next == from {
+                return Ok(tiles);
+            }
+            tiles.push(next);
+            index = next;
+            direction = *exits.iter().find(|&&d| d != direction.opposite()).unwrap();
+        }
+    }
 
-            ('L', South) => Position::new(position.index + 1, East, position.length),
-            ('L', West) => Position::new(position.index - position.length, North, position.length),
+    // shoelace formula for the area, Pick's theorem for the tiles inside
+    fn measure(&self, tiles: Vec<usize>) -> Loop {
+        let point = |index: usize| ((index % self.length) as i64, (index / self.length) as i64);
+        let area: i64 = tiles.iter().zip(tiles.iter().cycle().skip(1))
+            .map(|(&a, &b)| {
+                let ((ax, ay), (bx, by)) = (point(a), point(b));
+                ax * by - bx * ay
+            })
+            .sum();
+
+        let farthest = point(tiles[tiles.len() / 2]);
+        let enclosed = area.abs() / 2 - tiles.len() as i64 / 2 + 1;
+        Loop { farthest_point: (farthest.0 as usize, farthest.1 as usize), area: enclosed as u32, tiles }
+    }
+}
 
-            ('J', South) => Position::new(position.index - 1, West, position.length),
-            ('J', East) => Position::new(position.index - position.length, North, position.length),
+const PIPES: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
 
-            ('7', East) => Position::new(position.index + position.length, South, position.length),
-            ('7', North) => Position::new(position.index - 1, West, position.length),
+// the farthest point is counted from S, or for the other loops from their first tile in reading order
+#[derive(PartialEq, Debug)]
+struct Loop {
+    tiles: Vec<usize>,
+    farthest_point: (usize, usize),
+    area: u32,
+}
 
-            ('F', West) => Position::new(position.index + position.length, South, position.length),
-            ('F', North) => Position::new(position.index + 1, East, position.length),
+impl Loop {
+    pub fn length(&self) -> u32 {
+        self.tiles.len() as u32
+    }
 
-            _ => panic!("dead end")
-        }
+    pub fn farthest_steps(&self) -> u32 {
+        self.length() / 2
     }
 }
 
-#[derive(PartialEq, Debug, Copy, Clone)]
-struct Position {
-    index: usize,
-    direction: Direction,
-    length: usize,
+#[derive(PartialEq, Debug, Clone)]
+struct Tile {
+    tile: char,
 }
 
-impl Position {
-    pub fn new(index: usize, direction: Direction, length: usize) -> Position {
-        Position { index, direction, length }
+impl Tile {
+    pub fn new(tile: char) -> Tile {
+        Tile { tile }
+    }
+
+    // in the order of Direction
+    pub fn exits(&self) -> Vec<Direction> {
+        match self.tile {
+            '|' => vec![North, South],
+            '-' => vec![East, West],
+            'L' => vec![North, East],
+            'J' => vec![North, West],
+            '7' => vec![South, West],
+            'F' => vec![East, South],
+            _ => vec![],
+        }
     }
 }
 
@@ -200,3 +324,14 @@ enum Direction {
     South,
     West,
 }
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            North => South,
+            East => West,
+            South => North,
+            West => East,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parse;
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -74,6 +76,14 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Universe::from(input).sum_of_shortest_path_between_galaxies(1).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Universe::from(input).sum_of_shortest_path_between_galaxies(999_999).to_string()
+}
+
 struct Universe {
     lines: Vec<usize>,
     columns: Vec<usize>,
@@ -82,18 +92,15 @@ struct Universe {
 impl Universe {
     pub fn from(input: &str) -> Universe {
         let length = input.find('\n').unwrap();
-        let image: Vec<char> = input.chars().collect();
 
         let mut lines = vec![0; length];
         let mut columns = vec![0; length];
 
-        image.iter()
-            .filter(|&v| v != &'\n')
-            .enumerate()
-            .filter(|(_, &v)| v == '#')
-            .for_each(|(index, _)| {
-                lines[index / length] += 1;
-                columns[index % length] += 1;
+        parse::grid(input)
+            .filter(|(_, _, v)| *v == '#')
+            .for_each(|(x, y, _)| {
+                lines[y] += 1;
+                columns[x] += 1;
             });
 
         Universe { lines, columns }
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use crate::day12::Spring::{Damaged, Operational, Unknown};
+use crate::{parse, trace};
 
 #[cfg(test)]
 mod tests {
@@ -105,6 +106,14 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    HotSprings::from(input).arrangements().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    HotSprings::from_unfold(input).arrangements().to_string()
+}
+
 struct HotSprings {
     rows: Vec<Row>,
 }
@@ -118,6 +127,7 @@ impl HotSprings {
         HotSprings { rows: input.lines().map(Row::from_unfold).collect() }
     }
 
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(rows = self.rows.len())))]
     pub fn arrangements(&self) -> usize {
         self.rows.iter().map(|r| r.arrangements()).sum()
     }
@@ -130,18 +140,18 @@ struct Row {
 
 impl Row {
     pub fn from(input: &str) -> Row {
-        let (springs, records) = input.split_once(' ').unwrap();
+        let (springs, records) = parse::key_value(input, " ").unwrap();
         Row {
             springs: springs.chars().map(Spring::from).collect(),
-            records: records.split(',').map(|v| v.parse().unwrap()).collect(),
+            records: parse::separated(records, ',').unwrap(),
         }
     }
 
     pub fn from_unfold(input: &str) -> Row {
-        let (springs, records) = input.split_once(' ').unwrap();
+        let (springs, records) = parse::key_value(input, " ").unwrap();
         Row {
             springs: [springs; 5].join("?").chars().map(Spring::from).collect(),
-            records: [records; 5].join(",").split(',').map(|v| v.parse().unwrap()).collect(),
+            records: parse::separated(&[records; 5].join(","), ',').unwrap(),
         }
     }
 
@@ -156,8 +166,10 @@ impl Row {
         }
 
         if let Some(&arrangement) = cache.get(spring, record) {
+            trace::count!("cache_hits", 1);
             return arrangement;
         }
+        trace::count!("cache_misses", 1);
 
         let arrangement = match self.springs[spring] {
             Operational => self.is_operational(spring + 1, record, cache),
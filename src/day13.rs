@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -35,7 +36,7 @@ mod tests {
 
     #[test]
     fn finds_example_horizontal_reflection() {
-        let pattern = Pattern::from(EXAMPLE.split("\n\n").nth(1).unwrap());
+        let pattern = Pattern::from(parse::blocks(EXAMPLE)[1]);
 
         assert_eq!(pattern.horizontal_reflection(0), Some(4))
     }
@@ -52,7 +53,7 @@ mod tests {
 
     #[test]
     fn finds_example_vertical_reflection() {
-        let pattern = Pattern::from(EXAMPLE.split("\n\n").next().unwrap());
+        let pattern = Pattern::from(parse::blocks(EXAMPLE)[0]);
 
         assert_eq!(pattern.vertical_reflection(0), Some(5))
     }
@@ -104,13 +105,21 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Patterns::from(input).summarize(0).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Patterns::from(input).summarize(1).to_string()
+}
+
 struct Patterns {
     patterns: Vec<Pattern>,
 }
 
 impl Patterns {
     pub fn from(input: &str) -> Patterns {
-        Patterns { patterns: input.split("\n\n").map(Pattern::from).collect() }
+        Patterns { patterns: parse::blocks(input).into_iter().map(Pattern::from).collect() }
     }
 
     pub fn summarize(&self, smudged: u32) -> usize {
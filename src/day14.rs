@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -91,13 +92,23 @@ O.#..O.#.#
     }
 }
 
+pub fn part1(input: &str) -> String {
+    let mut rocks = Rocks::from(input);
+    rocks.total_load().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let mut rocks = Rocks::from(input);
+    rocks.total_load_cycling().to_string()
+}
+
 struct Rocks {
     platform: Vec<Vec<char>>,
 }
 
 impl Rocks {
     pub fn from(input: &str) -> Rocks {
-        Rocks { platform: input.lines().map(|l| l.chars().collect()).collect() }
+        Rocks { platform: parse::rows(input, |c| c) }
     }
 
     pub fn total_load(&mut self) -> usize {
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use regex::Regex;
+use crate::parse;
 
 #[cfg(test)]
 mod tests {
@@ -122,6 +122,14 @@ mod tests {
     }
 }
 
+pub fn part1(input: &str) -> String {
+    Sequence::from(input).hash().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Sequence::from(input).focusing_power().to_string()
+}
+
 struct Sequence {
     steps: Vec<String>,
 }
@@ -171,13 +179,14 @@ struct Lens {
 
 impl Lens {
     pub fn from(step: &str) -> Lens {
-        let regex = Regex::new(r"(?<label>[a-z]+)(?<operation>[=|-])(?<focal_length>[0-9]*)").unwrap();
-        let (_, [label, operation, focal_length]) = regex.captures(step).map(|g| g.extract()).unwrap();
+        let (label, operation, focal_length) = match parse::key_value(step, "=") {
+            Ok((label, focal_length)) => (label, '=', parse::number(focal_length).unwrap()),
+            Err(_) => (step.trim_end_matches('-'), '-', 0),
+        };
 
         let box_index = Sequence::hash_string(label);
-        let focal_length = focal_length.parse().unwrap_or(0);
 
-        Lens { label: label.to_string(), box_index, operation: operation.chars().next().unwrap(), focal_length }
+        Lens { label: label.to_string(), box_index, operation, focal_length }
     }
 }
 
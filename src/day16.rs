@@ -3,4 +3,6 @@ mod direction;
 mod grid;
 mod position;
 mod tile;
-mod _solve;
\ No newline at end of file
+mod _solve;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use crate::day16::position::Position;
 use crate::day16::tile::Tile;
+use crate::parse;
 
 pub struct Grid {
     pub tiles: HashMap<Position, Tile>,
@@ -10,8 +11,8 @@ pub struct Grid {
 
 impl Grid {
     pub fn from(input: &str) -> Grid {
-        let tiles = input.lines().enumerate()
-            .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, tile)| (Position::new(x as isize, y as isize), Tile::from(&tile))))
+        let tiles = parse::grid(input)
+            .map(|(x, y, tile)| (Position::new(x as isize, y as isize), Tile::from(&tile)))
             .collect();
         let max_y = input.lines().count() as isize;
         let max_x = input.lines().next().unwrap().chars().count() as isize;
@@ -2,4 +2,6 @@ mod _solve;
 mod block;
 mod city;
 mod direction;
-mod position;
\ No newline at end of file
+mod position;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,3 +1,13 @@
+use crate::day17::city::{City, Crucible};
+
+pub fn part1(input: &str) -> String {
+    City::from(input).shortest_path(&Crucible { min_move: 1, max_move: 3 }).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    City::from(input).shortest_path(&Crucible { min_move: 4, max_move: 10 }).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -3,6 +3,8 @@ use std::collections::{BinaryHeap, HashMap};
 use crate::day17::block::Block;
 use crate::day17::direction::{DIRECTIONS, START};
 use crate::day17::position::Position;
+use crate::parse;
+use crate::trace;
 
 pub struct Crucible {
     pub min_move: usize,
@@ -17,15 +19,14 @@ pub struct City {
 
 impl City {
     pub fn from(input: &str) -> City {
-        let blocks: Vec<Vec<usize>> = input.lines()
-            .map(|l| l.chars().filter_map(|c| c.to_digit(10)).map(|b| b as usize).collect())
-            .collect();
+        let blocks: Vec<Vec<usize>> = parse::rows(input, |c| c.to_digit(10).unwrap() as usize);
         let end = Position(blocks.len() - 1, blocks[0].len() - 1);
         City { blocks, end }
     }
 
     // Dijkstra: https://doc.rust-lang.org/std/collections/binary_heap/index.html
     // Min-heap: https://doc.rust-lang.org/alloc/collections/binary_heap/struct.BinaryHeap.html#min-heap
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(min_move = crucible.min_move, max_move = crucible.max_move)))]
     pub fn shortest_path(&self, crucible: &Crucible) -> usize {
         let mut visited = HashMap::new();
         let mut to_visit = BinaryHeap::new();
@@ -34,6 +35,7 @@ impl City {
 
         while let Some(Reverse(block)) = to_visit.pop() {
             if block.position == self.end {
+                trace::count!("states_visited", visited.len());
                 return block.cost;
             }
 
@@ -2,3 +2,5 @@ mod _solve;
 mod direction;
 mod dig_plan;
 mod dig_step;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
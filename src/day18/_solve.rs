@@ -1,3 +1,13 @@
+use crate::day18::dig_plan::DigPlan;
+
+pub fn part1(input: &str) -> String {
+    DigPlan::from(input).volume().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    DigPlan::from_color(input).volume().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use crate::day18::direction::Direction;
 use crate::day18::direction::Direction::{Down, Left, Right, Up};
+use crate::parse;
 
 #[derive(Debug, PartialEq)]
 pub struct DigStep {
@@ -11,13 +12,14 @@ pub struct DigStep {
 impl DigStep {
     pub fn from(input: &str) -> DigStep {
         let (direction, meters, _color) = input.split_whitespace().collect_tuple().unwrap();
-        DigStep { direction: Direction::from(direction), meters: meters.parse().unwrap() }
+        DigStep { direction: Direction::from(direction), meters: parse::number(meters).unwrap() }
     }
 
     pub fn from_color(input: &str) -> DigStep {
-        let (_, color) = input.split_once(" (#").unwrap();
-        let direction = &color[color.len() - 2..color.len() - 1];
-        let meters = &color[0..color.len() - 2];
+        let (_, color) = parse::key_value(input, "#").unwrap();
+        let color = color.trim_end_matches(')');
+        let direction = &color[color.len() - 1..];
+        let meters = &color[0..color.len() - 1];
         DigStep { direction: Direction::from_color(direction), meters: isize::from_str_radix(meters, 16).unwrap() }
     }
 
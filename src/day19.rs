@@ -2,4 +2,6 @@ mod _solve;
 mod workflow;
 mod rule;
 mod part;
-mod part_possibilities;
\ No newline at end of file
+mod part_possibilities;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,3 +1,14 @@
+use crate::day19::workflow::Workflows;
+
+pub fn part1(input: &str) -> String {
+    let mut workflows = Workflows::from(input);
+    workflows.accepted_parts_ratings().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    Workflows::from(input).accepted_parts_ratings_possibilities().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
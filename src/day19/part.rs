@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::parse;
 
 #[derive(Debug)]
 pub struct Part {
@@ -8,11 +9,10 @@ pub struct Part {
 
 impl Part {
     pub fn from(input: &str) -> Part {
-        let trimmed = &input[1..input.len() - 1];
-        let ratings: HashMap<char, usize> = trimmed
+        let ratings: HashMap<char, usize> = parse::delimited(input, '{', '}').unwrap()
             .split(',')
-            .filter_map(|p| p.split_once('='))
-            .map(|(p, r)| (p.chars().next().unwrap(), r.parse().unwrap()))
+            .map(|p| parse::key_value(p, "=").unwrap())
+            .map(|(p, r)| (p.chars().next().unwrap(), parse::number(r).unwrap()))
             .collect();
 
         Part { ratings, workflow: "in".to_string() }
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use crate::parse;
 
 #[derive(Debug)]
 pub struct Rule {
@@ -19,7 +20,7 @@ impl Rule {
     }
 
     pub fn from_greater_or_less(input: &str) -> Rule {
-        let (op, workflow) = input.split_once(':').unwrap();
+        let (op, workflow) = parse::section(input).unwrap();
 
         let mut chars = op.chars();
         let part = chars.next().unwrap();
@@ -29,7 +30,7 @@ impl Rule {
             Some('<') => Ordering::Less,
             _ => panic!()
         };
-        let rating = chars.as_str().parse().unwrap();
+        let rating = parse::number(chars.as_str()).unwrap();
         let next_workflow = workflow.to_string();
         Rule { part, operation, rating, next_workflow }
     }
@@ -3,6 +3,7 @@ use std::collections::{HashMap, VecDeque};
 use crate::day19::part::Part;
 use crate::day19::rule::Rule;
 use crate::day19::part_possibilities::{PartPossibilities, Possibilities};
+use crate::parse;
 
 pub struct Workflows {
     workflows: HashMap<String, Workflow>,
@@ -11,9 +12,9 @@ pub struct Workflows {
 
 impl Workflows {
     pub fn from(input: &str) -> Workflows {
-        let (workflows, parts) = input.split_once("\n\n").unwrap();
-        let workflows = workflows.lines().map(Workflow::from).collect();
-        let parts = parts.lines().map(Part::from).collect();
+        let blocks = parse::blocks(input);
+        let workflows = blocks[0].lines().map(Workflow::from).collect();
+        let parts = blocks[1].lines().map(Part::from).collect();
         Workflows { workflows, parts }
     }
 
@@ -52,9 +53,9 @@ pub struct Workflow {
 
 impl Workflow {
     fn from(input: &str) -> (String, Workflow) {
-        let (name, rules) = input.split_once('{').unwrap();
+        let (name, rules) = parse::key_value(input.trim_end_matches('}'), "{").unwrap();
 
-        let rules: Vec<Rule> = rules.strip_suffix('}').unwrap()
+        let rules: Vec<Rule> = rules
             .split(',')
             .map(Rule::from)
             .collect();
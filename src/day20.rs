@@ -3,4 +3,6 @@ mod pulses;
 mod modules;
 mod flip_flop_module;
 mod conjunction_module;
-mod broadcaster_module;
\ No newline at end of file
+mod broadcaster_module;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
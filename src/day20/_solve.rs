@@ -1,3 +1,18 @@
+use crate::day20::modules::Modules;
+
+pub fn part1(input: &str) -> String {
+    let mut modules = Modules::from(input);
+    modules.cycles_low_times_highs(1000).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let result: usize = ["vg", "ls", "vc", "nb"].map(|m| {
+        let mut modules = Modules::from(input);
+        modules.first_high_pulse(m)
+    }).iter().product();
+    result.to_string()
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::daily::daily_input;
@@ -3,6 +3,7 @@ use crate::day20::broadcaster_module::BroadcasterModule;
 use crate::day20::conjunction_module::ConjunctionModule;
 use crate::day20::flip_flop_module::FlipFlopModule;
 use crate::day20::pulses::{Pulse, PulseType, Pulses};
+use crate::{parse, trace};
 
 pub trait Module {
     fn receive(&mut self, pulse: &Pulse) -> Vec<Pulse>;
@@ -17,12 +18,12 @@ impl Modules {
         let mut modules: HashMap<String, Box<dyn Module>> = HashMap::new();
 
         for line in input.lines() {
-            let (source, outputs) = line.split_once(" -> ").unwrap();
+            let (source, outputs) = parse::key_value(line, "->").unwrap();
             let source = source.replace(['%', '&'], "");
             let outputs = outputs.split(", ").collect::<Vec<&str>>();
 
             let inputs: Vec<String> = input.lines()
-                .map(|l| l.split_once(" -> ").unwrap())
+                .map(|l| parse::key_value(l, "->").unwrap())
                 .filter(|(_, o)| o.contains(&source))
                 .map(|(s, _)| s.replace(['%', '&'], ""))
                 .collect();
@@ -67,11 +68,15 @@ impl Modules {
         pulses
     }
 
+    #[cfg_attr(feature = "trace", tracing::instrument(skip(self)))]
     pub fn first_high_pulse(&mut self, module: &str) -> usize {
         let mut cycle = 0;
         loop {
             let pulses = self.push_button();
             cycle += 1;
+            trace::count!("button_presses", 1);
+            trace::count!("pulses_sent", pulses.history.len());
+            trace::progress!(cycle, 1000);
 
             if pulses.history.iter().any(|p| p.source == module && p.pulse_type == PulseType::High) {
                 break;
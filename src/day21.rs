@@ -1,4 +1,6 @@
 mod _solve;
 mod garden_map;
 mod tile;
-mod position;
\ No newline at end of file
+mod position;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
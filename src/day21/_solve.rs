@@ -1,3 +1,15 @@
+use crate::day21::garden_map::GardenMap;
+
+pub fn part1(input: &str) -> String {
+    let mut map = GardenMap::from(input);
+    map.gardens_visited(64).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let mut map = GardenMap::from(input);
+    map.infinite_gardens_visited(26501365).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 use crate::day21::position::Position;
 use crate::day21::tile::Tile;
 use crate::day21::tile::Tile::Garden;
+use crate::parse;
 
 pub struct GardenMap {
     tiles: Vec<Vec<Tile>>,
@@ -12,15 +13,14 @@ pub struct GardenMap {
 
 impl GardenMap {
     pub fn from(input: &str) -> GardenMap {
-        let tiles: Vec<Vec<Tile>> = input.lines()
-            .map(|l| l.chars().map(Tile::from).collect())
-            .collect();
+        let tiles: Vec<Vec<Tile>> = parse::rows(input, Tile::from);
 
         let size = tiles.len();
 
-        let starting_position = input.lines().enumerate()
-            .flat_map(|(y, l)| l.chars().enumerate().filter(|(_, t)| *t == 'S').map(move |(x, _)| Position::new(x as isize, y as isize)))
-            .next().unwrap();
+        let starting_position = parse::grid(input)
+            .find(|(_, _, t)| *t == 'S')
+            .map(|(x, y, _)| Position::new(x as isize, y as isize))
+            .unwrap();
 
         GardenMap { tiles, size, starting_position, positions: HashSet::from([starting_position]) }
     }
@@ -1,3 +1,5 @@
 mod _solve;
 mod bricks;
-mod brick;
\ No newline at end of file
+mod brick;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,3 +1,15 @@
+use crate::day22::bricks::Bricks;
+
+pub fn part1(input: &str) -> String {
+    let mut bricks = Bricks::from(input);
+    bricks.safe_bricks_count().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let mut bricks = Bricks::from(input);
+    bricks.disintegrate_bricks_count().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
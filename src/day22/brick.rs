@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use crate::parse;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Brick(pub Xyz, pub Xyz);
@@ -7,12 +8,12 @@ type Xyz = (usize, usize, usize);
 
 impl Brick {
     pub fn from(line: &str) -> Brick {
-        let (end1, end2) = line.split_once('~').unwrap();
+        let (end1, end2) = parse::key_value(line, "~").unwrap();
         Brick(Self::parse_xyz(end1), Self::parse_xyz(end2))
     }
 
     fn parse_xyz(s: &str) -> Xyz {
-        s.split(',').map(|v| v.parse::<usize>().unwrap()).collect_tuple().unwrap()
+        parse::separated(s, ',').unwrap().into_iter().collect_tuple().unwrap()
     }
 
     pub fn is_in_the_air(&self) -> bool {
@@ -1,3 +1,5 @@
 mod _solve;
 mod trails;
-mod tile;
\ No newline at end of file
+mod tile;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,3 +1,15 @@
+use crate::day23::trails::Trails;
+
+pub fn part1(input: &str) -> String {
+    let mut trails = Trails::from(input);
+    trails.longest_hike().to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let mut trails = Trails::from_no_slopes(input);
+    trails.longest_hike().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -1,5 +1,7 @@
 use crate::day23::tile::Tile;
 use crate::day23::tile::Direction::{Down, Left, Right, Up};
+use crate::parse;
+use crate::trace;
 
 pub struct Trails {
     tiles: Vec<Vec<Tile>>,
@@ -7,18 +9,20 @@ pub struct Trails {
 
 impl Trails {
     pub fn from(input: &str) -> Trails {
-        Trails { tiles: input.lines().map(|l| l.chars().map(Tile::from).collect()).collect() }
+        Trails { tiles: parse::rows(input, Tile::from) }
     }
 
     pub fn from_no_slopes(input: &str) -> Trails {
-        Trails { tiles: input.lines().map(|l| l.chars().map(Tile::from_no_slopes).collect()).collect() }
+        Trails { tiles: parse::rows(input, Tile::from_no_slopes) }
     }
 
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
     pub fn longest_hike(&mut self) -> usize {
         self.tiles[0][1] = Tile::Walked;
         let mut hikes = Vec::new();
 
         self.hike_recursive(0, 1, 0, &mut hikes);
+        trace::count!("hikes_completed", hikes.len());
         hikes.into_iter().max().unwrap()
     }
 
@@ -2,3 +2,5 @@ mod _solve;
 mod xyz;
 mod hailstone;
 mod hailstones;
+
+pub use _solve::{part1, part2};
\ No newline at end of file
@@ -1,3 +1,14 @@
+use crate::day24::hailstones::Hailstones;
+
+pub fn part1(input: &str) -> String {
+    Hailstones::from(input).intersections_between(200000000000000.0, 400000000000000.0).to_string()
+}
+
+pub fn part2(input: &str) -> String {
+    let pos = Hailstones::from(input).rock_position_to_obliterate_all_hailstone_to_dust();
+    (pos.x + pos.y + pos.z).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -1,4 +1,5 @@
 use crate::day24::xyz::Xyz;
+use crate::parse;
 
 #[derive(Debug, PartialEq)]
 pub struct Hailstone {
@@ -9,7 +10,7 @@ pub struct Hailstone {
 
 impl Hailstone {
     pub fn from(input: &str) -> Hailstone {
-        let (position, velocity) = input.split_once(" @ ").unwrap();
+        let (position, velocity) = parse::key_value(input, "@").unwrap();
         let position = Xyz::from(position);
         let velocity = Xyz::from(velocity);
         let slope = velocity.y / velocity.x;
@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use crate::parse;
 
 #[derive(Debug, PartialEq)]
 pub struct Xyz {
@@ -13,7 +14,7 @@ impl Xyz {
     }
 
     pub fn from(input: &str) -> Xyz {
-        let (x, y, z) = input.split(", ").map(|v| v.parse::<f64>().unwrap()).collect_tuple().unwrap();
+        let (x, y, z) = parse::separated(input, ',').unwrap().into_iter().collect_tuple().unwrap();
         Xyz { x, y, z }
     }
 }
\ No newline at end of file
@@ -1,2 +1,4 @@
 mod _solve;
-mod diagram;
\ No newline at end of file
+mod diagram;
+
+pub use _solve::part1;
\ No newline at end of file
@@ -1,3 +1,9 @@
+use crate::day25::diagram::Diagram;
+
+pub fn part1(input: &str) -> String {
+    Diagram::from(input).fix_overload().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::daily::daily_input;
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
+use crate::parse;
 
 pub struct Diagram {
     connections: HashMap<String, HashSet<String>>,
@@ -10,9 +11,9 @@ impl Diagram {
         let mut connections = HashMap::new();
 
         for line in input.lines() {
-            let (component, components) = line.split_once(": ").unwrap();
+            let (component, components) = parse::section(line).unwrap();
 
-            for other in components.split(' ') {
+            for other in components.split_whitespace() {
                 Self::link(&mut connections, component, other);
                 Self::link(&mut connections, other, component);
             }
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use serde_json::Value;
+
+// Private leaderboard export from https://adventofcode.com/2023/leaderboard/private/view/{id}.json
+
+#[derive(PartialEq, Debug)]
+pub struct Leaderboard {
+    pub event: u32,
+    pub members: Vec<Member>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    pub local_score: u64,
+    pub stars: BTreeMap<(u32, u32), u64>,
+    pub star_indexes: BTreeMap<(u32, u32), u64>,
+}
+
+impl Leaderboard {
+    pub fn from(json: &str) -> Result<Leaderboard, String> {
+        let json: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let event = json["event"].as_str().and_then(|e| e.parse().ok()).ok_or("missing event")?;
+        let mut members: Vec<Member> = json["members"].as_object().ok_or("missing members")?
+            .values()
+            .map(Member::from)
+            .collect::<Result<_, _>>()?;
+        members.sort_by_key(|m| m.id);
+
+        Ok(Leaderboard { event, members })
+    }
+
+    pub fn days(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self.members.iter().flat_map(|m| m.stars.keys().map(|(day, _)| *day)).collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    pub fn unlock(&self, day: u32) -> u64 {
+        unlock(self.event, day)
+    }
+
+    // stars got in the same second are ordered by their star index, like on the site
+    pub fn ranking(&self, day: u32, part: u32) -> Vec<(&Member, u64)> {
+        let mut ranking: Vec<(&Member, u64)> = self.members.iter()
+            .filter_map(|m| m.stars.get(&(day, part)).map(|ts| (m, *ts)))
+            .collect();
+        ranking.sort_by_key(|(m, ts)| (*ts, m.star_indexes[&(day, part)], m.id));
+        ranking
+    }
+
+    pub fn local_scores(&self) -> Vec<(&Member, u64)> {
+        let mut scores: BTreeMap<u64, u64> = self.members.iter().map(|m| (m.id, 0)).collect();
+
+        for day in self.days() {
+            for part in [1, 2] {
+                for (rank, (member, _)) in self.ranking(day, part).into_iter().enumerate() {
+                    *scores.get_mut(&member.id).unwrap() += (self.members.len() - rank) as u64;
+                }
+            }
+        }
+
+        // equal scores go to whoever got their last star first
+        let mut scores: Vec<(&Member, u64)> = self.members.iter().map(|m| (m, scores[&m.id])).collect();
+        scores.sort_by_key(|(m, score)| (u64::MAX - score, m.last_star_index(), m.id));
+        scores
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = format!("{:>4}  {:<24} {:>5} {:>6} {:>8}\n", "Rank", "Member", "Stars", "Score", "Reported");
+        for (rank, (member, score)) in self.local_scores().into_iter().enumerate() {
+            report += &format!("{:>4}  {:<24} {:>5} {:>6} {:>8}\n", rank + 1, member.name, member.stars.len(), score, member.local_score);
+        }
+
+        for day in self.days() {
+            report += &format!("\nDay {day}\n{:>4}  {:<24} {:>10} {:>10} {:>10}\n", "Rank", "Member", "Part 1", "Part 2", "Delta");
+            for (rank, (member, _)) in self.ranking(day, 1).into_iter().enumerate() {
+                let part1 = member.stars.get(&(day, 1)).map(|ts| self.since_unlock(day, *ts));
+                let part2 = member.stars.get(&(day, 2)).map(|ts| self.since_unlock(day, *ts));
+                let delta = member.delta(day).map(duration);
+                report += &format!("{:>4}  {:<24} {:>10} {:>10} {:>10}\n", rank + 1, member.name, part1.unwrap_or_default(), part2.unwrap_or_default(), delta.unwrap_or_default());
+            }
+        }
+
+        report
+    }
+
+    // a star before the unlock comes from a skewed clock or another event year
+    fn since_unlock(&self, day: u32, timestamp: u64) -> String {
+        timestamp.checked_sub(self.unlock(day)).map(duration).unwrap_or("unknown".to_string())
+    }
+}
+
+impl Member {
+    fn from(json: &Value) -> Result<Member, String> {
+        let id = json["id"].as_u64().ok_or("missing member id")?;
+        let name = json["name"].as_str().map(|n| n.to_string()).unwrap_or(format!("(anonymous user #{id})"));
+        let local_score = json["local_score"].as_u64().unwrap_or(0);
+
+        let mut stars = BTreeMap::new();
+        let mut star_indexes = BTreeMap::new();
+        for (day, parts) in json["completion_day_level"].as_object().into_iter().flatten() {
+            for (part, star) in parts.as_object().into_iter().flatten() {
+                let key = (day.parse().map_err(|_| format!("invalid day {day}"))?, part.parse().map_err(|_| format!("invalid part {part}"))?);
+                stars.insert(key, star["get_star_ts"].as_u64().ok_or("missing star timestamp")?);
+                star_indexes.insert(key, star["star_index"].as_u64().unwrap_or(u64::MAX));
+            }
+        }
+
+        Ok(Member { id, name, local_score, stars, star_indexes })
+    }
+
+    pub fn delta(&self, day: u32) -> Option<u64> {
+        self.stars.get(&(day, 2))?.checked_sub(*self.stars.get(&(day, 1))?)
+    }
+
+    fn last_star_index(&self) -> u64 {
+        self.star_indexes.values().max().copied().unwrap_or(u64::MAX)
+    }
+}
+
+// puzzles unlock at midnight EST (05:00 UTC) from December 1st
+fn unlock(year: u32, day: u32) -> u64 {
+    days_since_epoch(year, 12, day) * 86_400 + 5 * 3_600
+}
+
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_since_epoch(year: u32, month: u32, day: u32) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year } as u64;
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) as u64 + 2) / 5 + day as u64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn duration(seconds: u64) -> String {
+    format!("{:02}:{:02}:{:02}", seconds / 3_600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "francois", "stars": 4, "local_score": 11, "global_score": 0, "last_star_ts": 1701494000,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1701407400, "star_index": 10}, "2": {"get_star_ts": 1701408000, "star_index": 20}},
+                      "2": {"1": {"get_star_ts": 1701493500, "star_index": 30}, "2": {"get_star_ts": 1701494000, "star_index": 40}}
+                  }},
+            "2": {"id": 2, "name": "marie", "stars": 3, "local_score": 9, "global_score": 0, "last_star_ts": 1701493300,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1701407000, "star_index": 5}, "2": {"get_star_ts": 1701409000, "star_index": 25}},
+                      "2": {"1": {"get_star_ts": 1701493300, "star_index": 28}}
+                  }},
+            "3": {"id": 3, "name": null, "stars": 0, "local_score": 0, "global_score": 0, "last_star_ts": 0, "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn parses_members_and_stars() {
+        let leaderboard = Leaderboard::from(EXAMPLE).unwrap();
+
+        assert_eq!(leaderboard.event, 2023);
+        assert_eq!(leaderboard.members.len(), 3);
+        assert_eq!(leaderboard.members[0].name, "francois");
+        assert_eq!(leaderboard.members[0].stars[&(1, 2)], 1701408000);
+        assert_eq!(leaderboard.members[2].name, "(anonymous user #3)");
+        assert!(leaderboard.members[2].stars.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_exports() {
+        assert!(Leaderboard::from("{").is_err());
+        assert_eq!(Leaderboard::from(r#"{"event": "2023"}"#), Err("missing members".to_string()));
+    }
+
+    #[test]
+    fn calculates_unlock_time() {
+        assert_eq!(unlock(2023, 1), 1701406800);
+        assert_eq!(unlock(2023, 25), 1703480400);
+    }
+
+    #[test]
+    fn calculates_delta_between_parts() {
+        let leaderboard = Leaderboard::from(EXAMPLE).unwrap();
+
+        assert_eq!(leaderboard.members[0].delta(1), Some(600));
+        assert_eq!(leaderboard.members[1].delta(1), Some(2000));
+        assert_eq!(leaderboard.members[1].delta(2), None);
+    }
+
+    #[test]
+    fn ranks_members_per_day() {
+        let leaderboard = Leaderboard::from(EXAMPLE).unwrap();
+
+        let ranking: Vec<&str> = leaderboard.ranking(2, 1).iter().map(|(m, _)| m.name.as_str()).collect();
+        assert_eq!(ranking, vec!["marie", "francois"]);
+    }
+
+    #[test]
+    fn recalculates_local_scores() {
+        let leaderboard = Leaderboard::from(EXAMPLE).unwrap();
+
+        let scores: Vec<(&str, u64)> = leaderboard.local_scores().iter().map(|(m, s)| (m.name.as_str(), *s)).collect();
+        assert_eq!(scores, vec![("francois", 3 + 2 + 3 + 2), ("marie", 3 + 2 + 3), ("(anonymous user #3)", 0)]);
+    }
+
+    const TIED: &str = r#"{
+        "event": "2023",
+        "members": {
+            "1": {"id": 1, "name": "francois", "local_score": 3, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1701406000, "star_index": 1}},
+                "2": {"1": {"get_star_ts": 1701493400, "star_index": 4}}
+            }},
+            "2": {"id": 2, "name": "marie", "local_score": 3, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1701407000, "star_index": 2}},
+                "2": {"1": {"get_star_ts": 1701493300, "star_index": 3}}
+            }}
+        }
+    }"#;
+
+    #[test]
+    fn breaks_ties_on_the_last_star_got() {
+        let leaderboard = Leaderboard::from(TIED).unwrap();
+
+        let scores: Vec<(&str, u64)> = leaderboard.local_scores().iter().map(|(m, s)| (m.name.as_str(), *s)).collect();
+        assert_eq!(scores, vec![("marie", 3), ("francois", 3)]);
+    }
+
+    #[test]
+    fn reports_stars_before_unlock_as_unknown() {
+        let report = Leaderboard::from(TIED).unwrap().report();
+
+        assert!(report.contains(&format!("{:>4}  {:<24} {:>10} {:>10} {:>10}", 1, "francois", "unknown", "", "")));
+    }
+
+    #[test]
+    fn reports_scores_and_days() {
+        let report = Leaderboard::from(EXAMPLE).unwrap().report();
+
+        assert!(report.contains(&format!("{:>4}  {:<24} {:>5} {:>6} {:>8}", 1, "francois", 4, 10, 11)));
+        assert!(report.contains(&format!("{:>4}  {:<24} {:>10} {:>10} {:>10}", 1, "marie", "00:03:20", "00:36:40", "00:33:20")));
+    }
+}
@@ -1,12 +1,18 @@
 #![allow(dead_code)]
+pub mod calendar;
 pub mod daily;
+pub mod leaderboard;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod trace;
 mod day01;
 mod day02;
 mod day03;
 mod day04;
 mod day05;
-mod day06;
-mod day07;
+pub mod day06;
+pub mod day07;
 mod day08;
 mod day09;
 mod day10;
@@ -0,0 +1,87 @@
+use std::{env, fs, panic, process};
+use std::panic::PanicHookInfo;
+use std::path::Path;
+use std::sync::Arc;
+use adventofcode_2023::{calendar, daily, day07, runner, scaffold, trace};
+use adventofcode_2023::calendar::Answers;
+use adventofcode_2023::leaderboard::Leaderboard;
+use adventofcode_2023::runner::Options;
+
+const USAGE: &str = "\
+usage: adventofcode-2023 <command>
+
+commands:
+  run [--timeout <secs>] [--workers <n>] [--include-ignored] [day...]
+      solves every day (or the given ones) in parallel and verifies the answers
+  new <day>
+      creates the day module with its tests, example input and answers entry
+  leaderboard <file.json>
+      prints scores and per day rankings from a saved private leaderboard
+  camel-cards [--jokers] [--json] [<input>]
+      ranks the day 7 hands (from the file, or the puzzle input) with their type and tie-breaks";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|a| a.as_str()) {
+        Some("run") => run(&args[1..]),
+        Some("new") => new(args.get(1)),
+        Some("leaderboard") => leaderboard(args.get(1)),
+        Some("camel-cards") => camel_cards(&args[1..]),
+        _ => exit(USAGE),
+    }
+}
+
+fn run(args: &[String]) {
+    let options = Options::from(args).unwrap_or_else(|e| exit(&e));
+    let summary = trace::init();
+    // failures of the days are reported in the table, any other panic keeps its message
+    let default_hook: Arc<dyn Fn(&PanicHookInfo) + Send + Sync> = Arc::from(panic::take_hook());
+    let hook = default_hook.clone();
+    panic::set_hook(Box::new(move |info| if !runner::in_day_thread() { hook(info) }));
+
+    let reports = runner::run(calendar::days(), &options, daily::daily_input);
+    panic::set_hook(Box::new(move |info| default_hook(info)));
+
+    print!("{}", runner::table(&reports, &Answers::load()));
+    if !summary.counters().is_empty() {
+        print!("\n{summary}");
+    }
+}
+
+fn new(day: Option<&String>) {
+    let day = day.and_then(|d| d.parse().ok()).unwrap_or_else(|| exit(USAGE));
+
+    match scaffold::create(Path::new("."), day) {
+        Ok(files) => files.iter().for_each(|f| println!("{}", f.display())),
+        Err(e) => exit(&e),
+    }
+}
+
+fn leaderboard(path: Option<&String>) {
+    let path = path.unwrap_or_else(|| exit(USAGE));
+    let json = fs::read_to_string(path).unwrap_or_else(|e| exit(&format!("{path}: {e}")));
+
+    match Leaderboard::from(&json) {
+        Ok(leaderboard) => print!("{}", leaderboard.report()),
+        Err(e) => exit(&format!("{path}: {e}")),
+    }
+}
+
+fn camel_cards(args: &[String]) {
+    let (flags, paths): (Vec<&String>, Vec<&String>) = args.iter().partition(|a| a.starts_with("--"));
+    if flags.iter().any(|f| *f != "--jokers" && *f != "--json") || paths.len() > 1 {
+        exit(USAGE);
+    }
+
+    let input = match paths.first() {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| exit(&format!("{path}: {e}"))),
+        None => daily::daily_input(7),
+    };
+    print!("{}", day07::ranking_report(&input, flags.iter().any(|f| *f == "--jokers"), flags.iter().any(|f| *f == "--json")));
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(2)
+}
@@ -0,0 +1,156 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+// Helpers return slices of the input so errors can point back at the offending text.
+
+#[derive(PartialEq, Debug)]
+pub struct ParseError<'a> {
+    pub message: String,
+    pub at: &'a str,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(message: &str, at: &'a str) -> ParseError<'a> {
+        ParseError { message: message.to_string(), at }
+    }
+
+    pub fn span(&self, source: &str) -> Range<usize> {
+        span(source, self.at)
+    }
+
+    pub fn report(&self, source: &str) -> String {
+        let span = self.span(source);
+        let line_start = source[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[span.start..].find('\n').map(|i| span.start + i).unwrap_or(source.len());
+        let line = line_number(source, self.at);
+        let column = source[line_start..span.start].chars().count() + 1;
+        let width = source[span.start..span.end.min(line_end)].chars().count().max(1);
+
+        format!("line {line}, column {column}: {}\n{}\n{}{}", self.message, &source[line_start..line_end], " ".repeat(column - 1), "^".repeat(width))
+    }
+}
+
+pub fn span(source: &str, part: &str) -> Range<usize> {
+    let start = (part.as_ptr() as usize).checked_sub(source.as_ptr() as usize)
+        .filter(|start| start + part.len() <= source.len())
+        .expect("part is not a slice of source");
+    start..start + part.len()
+}
+
+pub fn line_number(source: &str, part: &str) -> usize {
+    source[..span(source, part).start].matches('\n').count() + 1
+}
+
+pub fn number<T: FromStr>(input: &str) -> Result<T, ParseError<'_>> {
+    input.parse().map_err(|_| ParseError::new("expected a number", input))
+}
+
+pub fn numbers<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError<'_>> {
+    input.split_whitespace().map(number).collect()
+}
+
+pub fn separated<T: FromStr>(input: &str, separator: char) -> Result<Vec<T>, ParseError<'_>> {
+    input.split(separator).map(|n| number(n.trim())).collect()
+}
+
+pub fn blocks(input: &str) -> Vec<&str> {
+    input.split("\n\n")
+        .map(|b| b.trim_matches('\n'))
+        .filter(|b| !b.is_empty())
+        .collect()
+}
+
+pub fn key_value<'a>(line: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError<'a>> {
+    line.split_once(separator)
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .ok_or(ParseError::new(&format!("expected '{separator}'"), line))
+}
+
+pub fn section(block: &str) -> Result<(&str, &str), ParseError<'_>> {
+    key_value(block, ":")
+}
+
+pub fn labeled<'a>(block: &'a str, label: &str) -> Result<&'a str, ParseError<'a>> {
+    let (found, body) = section(block)?;
+    if found != label {
+        return Err(ParseError::new(&format!("expected '{label}'"), found));
+    }
+    Ok(body)
+}
+
+pub fn delimited(input: &str, open: char, close: char) -> Result<&str, ParseError<'_>> {
+    input.trim()
+        .strip_prefix(open).and_then(|i| i.strip_suffix(close))
+        .ok_or(ParseError::new(&format!("expected {open}...{close}"), input))
+}
+
+pub fn grid(input: &str) -> impl Iterator<Item=(usize, usize, char)> + '_ {
+    input.lines()
+        .enumerate()
+        .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, c)| (x, y, c)))
+}
+
+// one row per line, lines without any cell still counting as a row
+pub fn rows<T>(input: &str, cell: impl Fn(char) -> T) -> Vec<Vec<T>> {
+    let mut rows: Vec<Vec<T>> = input.lines().map(|_| vec![]).collect();
+    for (_, y, c) in grid(input) {
+        rows[y].push(cell(c));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(numbers::<u32>(" 41 48  83 "), Ok(vec![41, 48, 83]));
+        assert_eq!(numbers::<i64>("-3 0 3"), Ok(vec![-3, 0, 3]));
+        assert_eq!(separated::<usize>("1,1,3", ','), Ok(vec![1, 1, 3]));
+    }
+
+    #[test]
+    fn reports_invalid_numbers() {
+        let input = "seeds: 79 14\n55 1x3";
+
+        let error = numbers::<u64>(&input[13..]).unwrap_err();
+
+        assert_eq!(error.at, "1x3");
+        assert_eq!(error.span(input), 16..19);
+        assert_eq!(line_number(input, error.at), 2);
+        assert_eq!(error.report(input), "line 2, column 4: expected a number\n55 1x3\n   ^^^");
+    }
+
+    #[test]
+    fn splits_blocks_on_blank_lines() {
+        assert_eq!(blocks("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\n\n\nZZZ = (ZZZ, ZZZ)\n"), vec!["LLR", "AAA = (BBB, BBB)\nBBB = (AAA, ZZZ)", "ZZZ = (ZZZ, ZZZ)"]);
+    }
+
+    #[test]
+    fn parses_key_values() {
+        assert_eq!(key_value("AAA = (BBB, CCC)", "="), Ok(("AAA", "(BBB, CCC)")));
+        assert_eq!(key_value("rn1", "="), Err(ParseError::new("expected '='", "rn1")));
+    }
+
+    #[test]
+    fn parses_sections() {
+        assert_eq!(section("seed-to-soil map:\n50 98 2\n52 50 48"), Ok(("seed-to-soil map", "50 98 2\n52 50 48")));
+        assert_eq!(labeled("Time:      7  15   30", "Time"), Ok("7  15   30"));
+        assert_eq!(labeled("Distance:  9  40  200", "Time"), Err(ParseError::new("expected 'Time'", "Distance")));
+    }
+
+    #[test]
+    fn parses_delimited() {
+        assert_eq!(delimited("(BBB, CCC)", '(', ')'), Ok("BBB, CCC"));
+        assert_eq!(delimited("{x=787}", '(', ')'), Err(ParseError::new("expected (...)", "{x=787}")));
+    }
+
+    #[test]
+    fn parses_grid() {
+        let cells: Vec<(usize, usize, char)> = grid("#.\n.#").collect();
+
+        assert_eq!(cells, vec![(0, 0, '#'), (1, 0, '.'), (0, 1, '.'), (1, 1, '#')]);
+        assert_eq!(rows("#.\n\n.#", |c| c == '#'), vec![vec![true, false], vec![], vec![false, true]]);
+    }
+}
@@ -0,0 +1,292 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::calendar::{Answers, Day, Part, Status};
+use crate::trace;
+
+// day 23 recurses deep enough to overflow the default thread stack
+const STACK_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(PartialEq, Debug)]
+pub struct Options {
+    pub days: Vec<u32>,
+    pub timeout: Duration,
+    pub workers: usize,
+    pub include_ignored: bool,
+}
+
+impl Options {
+    pub fn from(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            days: vec![],
+            timeout: Duration::from_secs(60),
+            workers: thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+            include_ignored: false,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--timeout" => options.timeout = Duration::from_secs(Self::value(arg, args.next())?),
+                "--workers" => options.workers = Self::value::<usize>(arg, args.next())?.max(1),
+                "--include-ignored" => options.include_ignored = true,
+                day => options.days.push(day.parse().map_err(|_| format!("unknown day or option: {day}"))?),
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn value<T: std::str::FromStr>(option: &str, value: Option<&String>) -> Result<T, String> {
+        value.and_then(|v| v.parse().ok()).ok_or(format!("{option} expects a number"))
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Outcome {
+    Solved(String),
+    Failed(String),
+    Timeout,
+    Ignored,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub day: u32,
+    pub part: u32,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+enum Message {
+    Solved(Report),
+    Done(u32),
+}
+
+struct Running {
+    started: Instant,
+    parts: Vec<u32>,
+}
+
+pub fn run(days: Vec<Day>, options: &Options, input: fn(u32) -> String) -> Vec<Report> {
+    let (sender, receiver) = mpsc::channel();
+    let mut pending: VecDeque<Day> = days.into_iter()
+        .filter(|d| options.days.is_empty() || options.days.contains(&d.day))
+        .collect();
+    let mut running: HashMap<u32, Running> = HashMap::new();
+    let mut reports = vec![];
+
+    loop {
+        while running.len() < options.workers {
+            let Some(day) = pending.pop_front() else { break };
+            let (parts, ignored): (Vec<Part>, Vec<Part>) = day.parts.into_iter().partition(|p| options.include_ignored || !p.ignored);
+
+            reports.extend(ignored.iter().map(|p| Report { day: day.day, part: p.part, outcome: Outcome::Ignored, elapsed: Duration::ZERO }));
+            running.insert(day.day, Running { started: Instant::now(), parts: parts.iter().map(|p| p.part).collect() });
+            spawn(day.day, parts, input, sender.clone());
+        }
+
+        let Some(deadline) = running.values().map(|r| r.started + options.timeout).min() else { break };
+
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Message::Solved(report)) => {
+                if let Some(running) = running.get_mut(&report.day) {
+                    running.parts.retain(|&p| p != report.part);
+                    reports.push(report);
+                }
+            }
+            Ok(Message::Done(day)) => {
+                running.remove(&day);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let timed_out: Vec<u32> = running.iter().filter(|(_, r)| r.started + options.timeout <= Instant::now()).map(|(d, _)| *d).collect();
+                for day in timed_out {
+                    let parts = running.remove(&day).unwrap().parts;
+                    reports.extend(parts.into_iter().map(|part| Report { day, part, outcome: Outcome::Timeout, elapsed: options.timeout }));
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!("the runner holds a sender"),
+        }
+    }
+
+    reports.sort_by_key(|r| (r.day, r.part));
+    reports
+}
+
+// a timed out day keeps its thread until it finishes, its late answers are ignored
+fn spawn(day: u32, parts: Vec<Part>, input: fn(u32) -> String, sender: Sender<Message>) {
+    thread::Builder::new()
+        .name(format!("day{:0>2}", day))
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let input = panic::catch_unwind(|| input(day));
+
+            for part in parts {
+                let _span = trace::part(day, part.part);
+                let started = Instant::now();
+                let outcome = match &input {
+                    Ok(input) => match panic::catch_unwind(AssertUnwindSafe(|| (part.solve)(input))) {
+                        Ok(answer) => Outcome::Solved(answer),
+                        Err(error) => Outcome::Failed(panic_message(error.as_ref())),
+                    },
+                    Err(error) => Outcome::Failed(format!("no input: {}", panic_message(error.as_ref()))),
+                };
+                let _ = sender.send(Message::Solved(Report { day, part: part.part, outcome, elapsed: started.elapsed() }));
+            }
+            let _ = sender.send(Message::Done(day));
+        })
+        .unwrap();
+}
+
+pub fn in_day_thread() -> bool {
+    thread::current().name().is_some_and(is_day_thread)
+}
+
+fn is_day_thread(name: &str) -> bool {
+    name.len() == 5 && name.starts_with("day") && name[3..].chars().all(|c| c.is_ascii_digit())
+}
+
+fn panic_message(error: &(dyn Any + Send)) -> String {
+    error.downcast_ref::<String>().cloned()
+        .or(error.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or("panicked".to_string())
+}
+
+pub fn table(reports: &[Report], answers: &Answers) -> String {
+    let mut table = format!("{:>3} {:>4}  {:<20} {:>12}  {}\n", "Day", "Part", "Answer", "Time", "Status");
+    let mut total = Duration::ZERO;
+
+    for report in reports {
+        let (answer, status) = match &report.outcome {
+            Outcome::Solved(answer) => (answer.as_str(), status(answers.verify(report.day, report.part, answer)).to_string()),
+            Outcome::Failed(error) => ("", format!("failed: {error}")),
+            Outcome::Timeout => ("", "timeout".to_string()),
+            Outcome::Ignored => ("", "ignored".to_string()),
+        };
+        let time = if report.outcome == Outcome::Ignored { String::new() } else { format!("{:.1?}", report.elapsed) };
+        table += &format!("{:>3} {:>4}  {:<20} {:>12}  {}\n", report.day, report.part, answer, time, status);
+        total += report.elapsed;
+    }
+
+    table + &format!("{:>3} {:>4}  {:<20} {:>12}\n", "", "", "total", format!("{:.1?}", total))
+}
+
+fn status(status: Status) -> &'static str {
+    match status {
+        Status::Correct => "correct",
+        Status::Wrong => "wrong",
+        Status::Unknown => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(timeout: Duration) -> Options {
+        Options { days: vec![], timeout, workers: 2, include_ignored: false }
+    }
+
+    fn example(day: u32) -> String {
+        format!("input of day {day}")
+    }
+
+    fn echo(input: &str) -> String {
+        input.to_string()
+    }
+
+    fn fail(_input: &str) -> String {
+        panic!("dead end")
+    }
+
+    fn slow(_input: &str) -> String {
+        thread::sleep(Duration::from_secs(5));
+        "too late".to_string()
+    }
+
+    #[test]
+    fn parses_options() {
+        let args: Vec<String> = ["--timeout", "5", "--workers", "3", "--include-ignored", "7", "12"].map(String::from).to_vec();
+
+        let expected = Options { days: vec![7, 12], timeout: Duration::from_secs(5), workers: 3, include_ignored: true };
+        assert_eq!(Options::from(&args), Ok(expected));
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert_eq!(Options::from(&["--fast".to_string()]), Err("unknown day or option: --fast".to_string()));
+        assert_eq!(Options::from(&["--timeout".to_string()]), Err("--timeout expects a number".to_string()));
+    }
+
+    #[test]
+    fn runs_every_part_of_every_day() {
+        let days = vec![Day::new(2, echo, Some(fail)), Day::new(1, echo, None)];
+
+        let reports = run(days, &options(Duration::from_secs(5)), example);
+
+        let outcomes: Vec<(u32, u32, &Outcome)> = reports.iter().map(|r| (r.day, r.part, &r.outcome)).collect();
+        assert_eq!(outcomes, vec![
+            (1, 1, &Outcome::Solved("input of day 1".to_string())),
+            (2, 1, &Outcome::Solved("input of day 2".to_string())),
+            (2, 2, &Outcome::Failed("dead end".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn runs_selected_days_only() {
+        let days = vec![Day::new(1, echo, None), Day::new(2, echo, None)];
+        let options = Options { days: vec![2], ..options(Duration::from_secs(5)) };
+
+        let reports = run(days, &options, example);
+
+        assert_eq!(reports.iter().map(|r| r.day).collect::<Vec<u32>>(), vec![2]);
+    }
+
+    #[test]
+    fn times_out_slow_days() {
+        let days = vec![Day::new(1, echo, Some(slow)), Day::new(2, echo, None)];
+
+        let reports = run(days, &options(Duration::from_millis(100)), example);
+
+        let outcomes: Vec<&Outcome> = reports.iter().map(|r| &r.outcome).collect();
+        assert_eq!(outcomes, vec![&Outcome::Solved("input of day 1".to_string()), &Outcome::Timeout, &Outcome::Solved("input of day 2".to_string())]);
+    }
+
+    #[test]
+    fn skips_ignored_parts() {
+        let days = vec![Day::new(1, echo, Some(slow)).ignore(2)];
+
+        let reports = run(days, &options(Duration::from_secs(5)), example);
+
+        assert_eq!(reports[1].outcome, Outcome::Ignored);
+    }
+
+    #[test]
+    fn recognizes_day_threads() {
+        assert!(is_day_thread("day07"));
+        assert!(!is_day_thread("day07::tests::solves_part1"));
+        assert!(!is_day_thread("main"));
+    }
+
+    #[test]
+    fn verifies_answers_in_table() {
+        let answers = Answers::from("1 1 142\n1 2 281");
+        let reports = vec![
+            Report { day: 1, part: 1, outcome: Outcome::Solved("142".to_string()), elapsed: Duration::from_millis(2) },
+            Report { day: 1, part: 2, outcome: Outcome::Solved("280".to_string()), elapsed: Duration::from_millis(3) },
+            Report { day: 2, part: 1, outcome: Outcome::Timeout, elapsed: Duration::from_secs(1) },
+        ];
+
+        let table = table(&reports, &answers);
+
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].ends_with("correct"));
+        assert!(lines[2].ends_with("wrong"));
+        assert!(lines[3].ends_with("timeout"));
+        assert!(lines[4].contains("1.0s"));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MODULE: &str = "\
+mod _solve;
+
+pub use _solve::{part1, part2};
+";
+
+const SOLVE: &str = "\
+pub fn part1(input: &str) -> String {
+    todo!(\"day {day} part 1 with {} lines\", input.lines().count())
+}
+
+pub fn part2(input: &str) -> String {
+    todo!(\"day {day} part 2 with {} lines\", input.lines().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::daily::{daily_example, daily_input};
+    use super::*;
+
+    #[test]
+    fn solves_example_part1() {
+        assert_eq!(part1(&daily_example({day})), \"\");
+    }
+
+    #[test]
+    fn solves_part1() {
+        assert_eq!(part1(&daily_input({day})), \"\");
+    }
+
+    #[test]
+    fn solves_example_part2() {
+        assert_eq!(part2(&daily_example({day})), \"\");
+    }
+
+    #[test]
+    fn solves_part2() {
+        assert_eq!(part2(&daily_input({day})), \"\");
+    }
+}
+";
+
+pub fn create(root: &Path, day: u32) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {day} is not between 1 and 25"));
+    }
+
+    let src = root.join("src");
+    let module = format!("day{:0>2}", day);
+    if src.join(format!("{module}.rs")).exists() {
+        return Err(format!("{module} already exists"));
+    }
+
+    let lib = register_module(&read(&src.join("lib.rs"))?, day)?;
+    let calendar = register_day(&read(&src.join("calendar.rs"))?, day)?;
+    let answers = register_answers(&read(&src.join("answers.txt")).unwrap_or_default(), day);
+
+    let files = vec![
+        (src.join(format!("{module}.rs")), MODULE.to_string()),
+        (src.join(&module).join("_solve.rs"), SOLVE.replace("{day}", &day.to_string())),
+        (src.join(format!("{module}.input")), String::new()),
+        (src.join("lib.rs"), lib),
+        (src.join("calendar.rs"), calendar),
+        (src.join("answers.txt"), answers),
+    ];
+
+    fs::create_dir_all(src.join(&module)).map_err(|e| e.to_string())?;
+    for (path, content) in files.iter() {
+        fs::write(path, content).map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// zero padded module names sort in day order
+fn register_module(lib: &str, day: u32) -> Result<String, String> {
+    let module = format!("mod day{:0>2};", day);
+    let modules: Vec<&str> = lib.lines().filter(|l| l.starts_with("mod day")).collect();
+    let first = modules.first().ok_or("no day registered in lib.rs")?;
+
+    Ok(match modules.iter().rev().find(|m| **m < module.as_str()) {
+        Some(previous) => insert_after(lib, previous, &module),
+        None => insert_before(lib, first, &module),
+    })
+}
+
+fn register_day(calendar: &str, day: u32) -> Result<String, String> {
+    let module = format!("day{:0>2}", day);
+    let imported: Vec<&str> = calendar.lines()
+        .filter_map(|l| l.strip_prefix("use crate::{")?.strip_suffix("};"))
+        .flat_map(|modules| modules.split(", "))
+        .collect();
+    let first = imported.first().ok_or("no day imported in calendar.rs")?;
+    let calendar = match imported.iter().rev().find(|m| **m < module.as_str()) {
+        Some(previous) => calendar.replacen(previous, &format!("{previous}, {module}"), 1),
+        None => calendar.replacen(first, &format!("{module}, {first}"), 1),
+    };
+
+    let entries: Vec<(u32, &str)> = calendar.lines()
+        .filter_map(|l| Some((l.trim_start().strip_prefix("Day::new(")?.split(',').next()?.parse().ok()?, l)))
+        .collect();
+    let (_, first) = entries.first().ok_or("no day registered in calendar.rs")?;
+    let indent = &first[..first.len() - first.trim_start().len()];
+    let entry = format!("{indent}Day::new({day}, {module}::part1, Some({module}::part2)),");
+
+    Ok(match entries.iter().rev().find(|(d, _)| *d < day) {
+        Some((_, previous)) => insert_after(&calendar, previous, &entry),
+        None => insert_before(&calendar, first, &entry),
+    })
+}
+
+// answers are filled in once the puzzle is solved, until then the runner reports them as unknown
+fn register_answers(answers: &str, day: u32) -> String {
+    let separator = if answers.is_empty() || answers.ends_with('\n') { "" } else { "\n" };
+    format!("{answers}{separator}{day} 1\n{day} 2\n")
+}
+
+fn insert_after(content: &str, line: &str, new_line: &str) -> String {
+    let at = content.find(line).unwrap() + line.len();
+    format!("{}\n{}{}", &content[..at], new_line, &content[at..])
+}
+
+fn insert_before(content: &str, line: &str, new_line: &str) -> String {
+    let at = content.find(line).unwrap();
+    format!("{}{}\n{}", &content[..at], new_line, &content[at..])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use super::*;
+
+    #[test]
+    fn registers_module_after_last_day() {
+        let lib = "pub mod daily;\nmod day01;\nmod day02;";
+
+        assert_eq!(register_module(lib, 3), Ok("pub mod daily;\nmod day01;\nmod day02;\nmod day03;".to_string()));
+    }
+
+    #[test]
+    fn registers_day_in_calendar() {
+        let calendar = "use crate::{day01, day02};\n\npub fn days() -> Vec<Day> {\n    vec![\n        Day::new(1, day01::part1, Some(day01::part2)),\n        Day::new(2, day02::part1, None),\n    ]\n}\n";
+
+        let expected = "use crate::{day01, day02, day03};\n\npub fn days() -> Vec<Day> {\n    vec![\n        Day::new(1, day01::part1, Some(day01::part2)),\n        Day::new(2, day02::part1, None),\n        Day::new(3, day03::part1, Some(day03::part2)),\n    ]\n}\n";
+        assert_eq!(register_day(calendar, 3), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let lib = "pub mod daily;\nmod day02;\nmod day04;\npub mod runner;";
+        let calendar = "use crate::{day02, day04};\n\npub fn days() -> Vec<Day> {\n    vec![\n        Day::new(2, day02::part1, None),\n        Day::new(4, day04::part1, None),\n    ]\n}\n";
+
+        assert_eq!(register_module(lib, 3), Ok("pub mod daily;\nmod day02;\nmod day03;\nmod day04;\npub mod runner;".to_string()));
+        assert_eq!(register_module(lib, 1), Ok("pub mod daily;\nmod day01;\nmod day02;\nmod day04;\npub mod runner;".to_string()));
+
+        let expected = "use crate::{day01, day02, day04};\n\npub fn days() -> Vec<Day> {\n    vec![\n        Day::new(1, day01::part1, Some(day01::part2)),\n        Day::new(2, day02::part1, None),\n        Day::new(4, day04::part1, None),\n    ]\n}\n";
+        assert_eq!(register_day(calendar, 1), Ok(expected.to_string()));
+        let expected = "use crate::{day02, day03, day04};\n\npub fn days() -> Vec<Day> {\n    vec![\n        Day::new(2, day02::part1, None),\n        Day::new(3, day03::part1, Some(day03::part2)),\n        Day::new(4, day04::part1, None),\n    ]\n}\n";
+        assert_eq!(register_day(calendar, 3), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn rejects_days_outside_the_calendar() {
+        assert_eq!(create(Path::new("."), 0), Err("day 0 is not between 1 and 25".to_string()));
+        assert_eq!(create(Path::new("."), 26), Err("day 26 is not between 1 and 25".to_string()));
+    }
+
+    #[test]
+    fn registers_unknown_answers() {
+        assert_eq!(register_answers("1 1 53080\n1 2 53268", 2), "1 1 53080\n1 2 53268\n2 1\n2 2\n");
+        assert_eq!(register_answers("", 1), "1 1\n1 2\n");
+    }
+
+    #[test]
+    fn creates_day_module() {
+        let root = env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "mod day01;\n").unwrap();
+        fs::write(root.join("src/calendar.rs"), "use crate::{day01};\n    vec![\n        Day::new(1, day01::part1, None),\n    ]\n").unwrap();
+
+        let created = create(&root, 2).unwrap();
+
+        assert_eq!(created.len(), 6);
+        assert_eq!(fs::read_to_string(root.join("src/day02.rs")).unwrap(), MODULE);
+        assert!(fs::read_to_string(root.join("src/day02/_solve.rs")).unwrap().contains("daily_example(2)"));
+        assert_eq!(fs::read_to_string(root.join("src/day02.input")).unwrap(), "");
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), "mod day01;\nmod day02;\n");
+        assert_eq!(fs::read_to_string(root.join("src/answers.txt")).unwrap(), "2 1\n2 2\n");
+        assert_eq!(create(&root, 2), Err("day02 already exists".to_string()));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
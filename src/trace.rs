@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+// Instrumentation is compiled in with `--features trace`, the macros below are no-ops otherwise.
+// Logs are filtered with RUST_LOG (default: info), counters are summed up in a Summary.
+
+#[cfg(feature = "trace")]
+macro_rules! count {
+    ($counter:literal, $value:expr) => {
+        tracing::trace!(counter = $counter, value = $value as u64)
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! count {
+    ($counter:literal, $value:expr) => {
+        let _ = &$value;
+    };
+}
+
+#[cfg(feature = "trace")]
+macro_rules! progress {
+    ($step:expr, $every:expr $(, $key:ident = $value:expr)*) => {
+        if $step % $every == 0 {
+            tracing::info!(step = $step $(, $key = $value)*, "progress")
+        }
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! progress {
+    ($step:expr, $every:expr $(, $key:ident = $value:expr)*) => {
+        let _ = &$step;
+    };
+}
+
+pub(crate) use count;
+pub(crate) use progress;
+
+#[derive(Clone, Default)]
+pub struct Summary {
+    counters: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl Summary {
+    pub fn counters(&self) -> BTreeMap<String, u64> {
+        self.counters.lock().unwrap().clone()
+    }
+
+    pub fn reset(&self) {
+        self.counters.lock().unwrap().clear();
+    }
+
+    fn add(&self, counter: &str, value: u64) {
+        *self.counters.lock().unwrap().entry(counter.to_string()).or_insert(0) += value;
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (counter, value) in self.counters() {
+            writeln!(f, "{counter:<24} {value:>16}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "trace")]
+pub fn init() -> Summary {
+    use tracing_subscriber::{EnvFilter, Layer};
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let summary = Summary::default();
+    let log = tracing_subscriber::fmt::layer()
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")));
+
+    tracing_subscriber::registry()
+        .with(log)
+        .with(Counters(summary.clone()))
+        .init();
+
+    summary
+}
+
+#[cfg(not(feature = "trace"))]
+pub fn init() -> Summary {
+    Summary::default()
+}
+
+#[cfg(feature = "trace")]
+pub fn part(day: u32, part: u32) -> tracing::span::EnteredSpan {
+    tracing::info_span!("solve", day, part).entered()
+}
+
+#[cfg(not(feature = "trace"))]
+pub fn part(_day: u32, _part: u32) -> PartSpan {
+    PartSpan
+}
+
+#[cfg(not(feature = "trace"))]
+pub struct PartSpan;
+
+#[cfg(feature = "trace")]
+struct Counters(Summary);
+
+#[cfg(feature = "trace")]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Counters {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let mut visitor = CounterVisitor::default();
+        event.record(&mut visitor);
+
+        if let (Some(counter), Some(value)) = (visitor.counter, visitor.value) {
+            self.0.add(&counter, value);
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+#[derive(Default)]
+struct CounterVisitor {
+    counter: Option<String>,
+    value: Option<u64>,
+}
+
+#[cfg(feature = "trace")]
+impl tracing::field::Visit for CounterVisitor {
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        if field.name() == "value" {
+            self.value = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "counter" {
+            self.counter = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &tracing::field::Field, _value: &dyn std::fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_counters() {
+        let summary = Summary::default();
+        summary.add("cache_hits", 2);
+        summary.add("cache_hits", 3);
+        summary.add("states_visited", 7);
+
+        assert_eq!(summary.counters(), BTreeMap::from([("cache_hits".to_string(), 5), ("states_visited".to_string(), 7)]));
+        assert_eq!(summary.to_string(), format!("{:<24} {:>16}\n{:<24} {:>16}\n", "cache_hits", 5, "states_visited", 7));
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn counts_events_from_instrumented_code() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let summary = Summary::default();
+        let subscriber = tracing_subscriber::registry().with(Counters(summary.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..3 {
+                count!("cache_hits", 1);
+            }
+            progress!(10, 5, remaining = 1);
+        });
+
+        assert_eq!(summary.counters(), BTreeMap::from([("cache_hits".to_string(), 3)]));
+    }
+}